@@ -0,0 +1,37 @@
+use chrono::{Duration, DurationRound, Utc};
+use serde_json::json;
+use weather_cli::weather::WeatherData;
+
+/// A week of hourly entries starting at the current hour, shaped like a met.no compact response
+pub fn sample_weather_data() -> WeatherData {
+    let start = Utc::now().duration_trunc(Duration::hours(1)).unwrap();
+    let timeseries: Vec<_> = (0..24 * 8)
+        .map(|hour| {
+            json!({
+                "time": start + Duration::hours(hour),
+                "data": {
+                    "instant": { "details": {
+                        "air_pressure_at_sea_level": 1013.2,
+                        "air_temperature": 12.5,
+                        "cloud_area_fraction": 40.0,
+                        "relative_humidity": 71.3,
+                        "wind_from_direction": 225.0,
+                        "wind_speed": 4.2
+                    } },
+                    "next_1_hours": { "summary": { "symbol_code": "partlycloudy_day" } },
+                    "next_12_hours": { "summary": { "symbol_code": "cloudy" } }
+                }
+            })
+        })
+        .collect();
+
+    serde_json::from_value(json!({
+        "type": "Feature",
+        "geometry": { "type": "Point", "coordinates": [10.75, 59.91, 0.0] },
+        "properties": {
+            "meta": { "updated_at": start, "units": {} },
+            "timeseries": timeseries
+        }
+    }))
+    .unwrap()
+}
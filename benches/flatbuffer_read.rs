@@ -0,0 +1,82 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use planus::ReadAsRoot;
+use weather_cli::export::weather_fbs::weather::WeatherDataRef;
+use weather_cli::weather::WeatherData;
+
+mod common;
+
+use common::sample_weather_data;
+
+/// Reads every instant air temperature straight out of the buffer, without deserializing
+fn flatbuffer_temperatures(bytes: &[u8]) -> Vec<Option<f64>> {
+    let root = WeatherDataRef::read_as_root(bytes).unwrap();
+    root.timeseries()
+        .unwrap()
+        .map(|timeseries| {
+            timeseries
+                .iter()
+                .map(|timeseries| {
+                    let instant = timeseries.unwrap().instant().unwrap()?;
+                    instant.air_temperature().unwrap()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "capnp")]
+mod capnp_read {
+    use weather_cli::export::weather_capnp::weather_data;
+
+    /// Reads the instant air temperature column of `weather.capnp` without deserializing
+    pub fn capnp_temperatures(mut bytes: &[u8]) -> Vec<Option<f64>> {
+        let message =
+            capnp::serialize::read_message_from_flat_slice(&mut bytes, Default::default()).unwrap();
+        let root = message.get_root::<weather_data::Reader>().unwrap();
+        let temperatures = root.get_timeseries().unwrap().get_air_temperature().unwrap();
+
+        temperatures
+            .iter()
+            .map(|temperature| (!temperature.is_nan()).then_some(temperature))
+            .collect()
+    }
+}
+
+fn temperatures(data: &WeatherData) -> Vec<Option<f64>> {
+    data.properties
+        .timeseries
+        .iter()
+        .map(|timeseries| timeseries.data.instant.details.air_temperature)
+        .collect()
+}
+
+fn bench_read_latency(c: &mut Criterion) {
+    let data = sample_weather_data();
+    let json = serde_json::to_vec(&data).unwrap();
+    let bincode = bincode::serialize(&data).unwrap();
+    let flatbuffer = data.to_flatbuffer();
+    assert_eq!(flatbuffer_temperatures(&flatbuffer), temperatures(&data));
+    #[cfg(feature = "capnp")]
+    let capnp = data.to_capnp().unwrap();
+    #[cfg(feature = "capnp")]
+    assert_eq!(capnp_read::capnp_temperatures(&capnp), temperatures(&data));
+
+    let mut group = c.benchmark_group("read_temperatures");
+    group.bench_function("json", |b| {
+        b.iter(|| temperatures(&serde_json::from_slice(black_box(&json)).unwrap()))
+    });
+    group.bench_function("bincode", |b| {
+        b.iter(|| temperatures(&bincode::deserialize(black_box(&bincode)).unwrap()))
+    });
+    group.bench_function("flatbuffers", |b| {
+        b.iter(|| flatbuffer_temperatures(black_box(&flatbuffer)))
+    });
+    #[cfg(feature = "capnp")]
+    group.bench_function("capnp", |b| {
+        b.iter(|| capnp_read::capnp_temperatures(black_box(&capnp)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_read_latency);
+criterion_main!(benches);
@@ -0,0 +1,53 @@
+use clap::Parser;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use weather_cli::args::WeatherArgs;
+use weather_cli::batch::{parallel_format, WeatherReport};
+use weather_cli::weather::DisplayOptions;
+
+mod common;
+
+use common::sample_weather_data;
+
+fn bench_parallel_format(c: &mut Criterion) {
+    let args = WeatherArgs::parse_from(["weather-cli", "bench", "-d", "week", "-o", "detailed"]);
+    let mut group = c.benchmark_group("format_reports");
+
+    for count in [5, 10, 20] {
+        let reports: Vec<_> = (0..count)
+            .map(|index| WeatherReport {
+                location: format!("location-{}", index),
+                display_name: format!("Location {}", index),
+                data: sample_weather_data(),
+            })
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("sequential", count),
+            &reports,
+            |b, reports| {
+                b.iter(|| {
+                    reports
+                        .iter()
+                        .map(|report| {
+                            report.data.format(
+                                args.duration.unwrap(),
+                                report.display_name.clone(),
+                                args.output_mode.unwrap(),
+                                &DisplayOptions::from(&args),
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("parallel", count),
+            &reports,
+            |b, reports| b.iter(|| parallel_format(reports, &args)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parallel_format);
+criterion_main!(benches);
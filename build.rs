@@ -0,0 +1,47 @@
+//! Generates the code of the serialization formats enabled as features from their schemas
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // needs the `capnp` tool of Cap'n Proto, e.g. the `capnproto` package
+    #[cfg(feature = "capnp")]
+    {
+        println!("cargo:rerun-if-changed=weather.capnp");
+        capnpc::CompilerCommand::new()
+            .file("weather.capnp")
+            .default_parent_module(vec![String::from("export"), String::from("capnp")])
+            .run()
+            .expect("weather.capnp compiles");
+    }
+
+    #[cfg(feature = "flatbuffers")]
+    {
+        println!("cargo:rerun-if-changed=weather.fbs");
+        let declarations =
+            planus_translation::translate_files(&["weather.fbs"]).expect("weather.fbs compiles");
+        // unformatted, so building does not need rustfmt
+        let code = planus_codegen::generate_rust(&declarations, false)
+            .expect("weather.fbs generates Rust code");
+        let out_dir = std::path::PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
+        std::fs::write(out_dir.join("weather_fbs.rs"), code).expect("OUT_DIR is writable");
+    }
+
+    // protoc comes with protoc-bin-vendored, so building needs no Protocol Buffers install
+    #[cfg(feature = "protobuf")]
+    {
+        println!("cargo:rerun-if-changed=weather.proto");
+        let mut config = prost_build::Config::new();
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("protoc is vendored");
+        config.protoc_executable(protoc);
+        // with the server side of the `weather.Weather` service when serving it
+        #[cfg(feature = "grpc")]
+        tonic_build::configure()
+            .build_client(false)
+            .compile_protos_with_config(config, &["weather.proto"], &["."])
+            .expect("weather.proto compiles");
+        #[cfg(not(feature = "grpc"))]
+        config
+            .compile_protos(&["weather.proto"], &["."])
+            .expect("weather.proto compiles");
+    }
+}
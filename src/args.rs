@@ -1,7 +1,14 @@
 use clap:: {
     Parser,
+    Subcommand,
     ValueEnum,
 };
+use chrono::NaiveDate;
+#[cfg(feature = "serve")]
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use crate::weather::PrecipitationType;
 
 #[derive(Parser, Debug)]
 #[command(version, about)]
@@ -9,23 +16,652 @@ use clap:: {
 pub struct WeatherArgs {
     // #[command(subcommand)]
     // pub format: LocationFormat,
-    /// Location of the forecast
-    pub location: String,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Location of the forecast, or the alias of a saved location
+    #[arg(
+        required_unless_present_any = [
+            "batch_file",
+            "print_schema",
+            "history",
+            "clear_history",
+            "generate_completion",
+        ],
+        env = "WEATHER_CLI_LOCATION"
+    )]
+    pub location: Option<String>,
     /// Duration of the forecast
-    #[arg(short, long, default_value = "now")]
+    #[arg(short, long, default_value = "now", env = "WEATHER_CLI_DURATION")]
     pub duration: Option<DurationType>, 
 
+    /// First day of a custom forecast range, as YYYY-MM-DD
+    #[arg(long, value_name = "DATE")]
+    pub start_date: Option<NaiveDate>,
+
+    /// Last day of a custom forecast range, as YYYY-MM-DD
+    #[arg(long, value_name = "DATE")]
+    pub end_date: Option<NaiveDate>,
+
+    /// Show the next N hours instead of --duration, from 1 to 240
+    #[arg(long, value_name = "N", conflicts_with_all = ["start_date", "end_date"])]
+    pub hours_ahead: Option<u32>,
+
+    /// Show the forecast from N hours after the current time, e.g. for when you arrive somewhere,
+    /// up to 240
+    #[arg(long, value_name = "N")]
+    pub skip_hours: Option<u32>,
+
+    /// Time zone of the displayed times, e.g. Europe/Oslo; days still start at midnight UTC
+    /// [default: UTC]
+    #[arg(long, value_name = "TZ", env = "WEATHER_CLI_TIMEZONE")]
+    pub timezone: Option<chrono_tz::Tz>,
+
     /// Output format of the forecast
-    #[arg(short, long, default_value = "compact")]
+    #[arg(short, long, default_value = "compact", env = "WEATHER_CLI_OUTPUT_MODE")]
     pub output_mode: Option<OutputMode>,
+
+    /// Unit used to display temperatures [default: celsius, or fahrenheit with --imperial]
+    #[arg(long, env = "WEATHER_CLI_TEMP_UNIT")]
+    pub temp_unit: Option<TempUnit>,
+
+    /// Unit used to display air pressure [default: hectopascal, or inches-of-mercury with
+    /// --imperial]
+    #[arg(long, env = "WEATHER_CLI_PRESSURE_UNIT")]
+    pub pressure_unit: Option<PressureUnit>,
+
+    /// Unit used to display wind speed [default: meters-per-second, or miles-per-hour with
+    /// --imperial]
+    #[arg(long, env = "WEATHER_CLI_SPEED_UNIT")]
+    pub speed_unit: Option<SpeedUnit>,
+
+    /// Use metric units, the default
+    #[arg(long, conflicts_with = "imperial")]
+    pub metric: bool,
+
+    /// Use imperial units for every unit not given explicitly
+    #[arg(long)]
+    pub imperial: bool,
+
+    /// Colors of the temperatures and weather conditions, for a dark or light terminal
+    #[arg(long, default_value = "auto", env = "WEATHER_CLI_COLOR_THEME")]
+    pub color_theme: Option<ColorTheme>,
+
+    /// Show the perceived temperature (wind chill or heat index) next to the air temperature
+    #[arg(long)]
+    pub feels_like: bool,
+
+    /// Describe the weather without emoji
+    #[arg(long)]
+    pub no_emoji: bool,
+
+    /// Print the unit of every field before the forecast
+    #[arg(long)]
+    pub show_units: bool,
+
+    /// Fail when the forecast was last updated more than this many minutes ago, up to a week
+    #[arg(long, value_name = "MINUTES")]
+    pub max_age: Option<u64>,
+
+    /// Fields shown in detailed and CSV output, all of them when not given
+    #[arg(long, value_name = "FIELD,...", value_delimiter = ',')]
+    pub columns: Vec<ColumnField>,
+
+    /// Maximum width of the output, in columns; defaults to the terminal width, or 80
+    #[arg(long, value_name = "COLUMNS")]
+    pub width: Option<usize>,
+
+    /// Number of retries for failed requests
+    #[arg(long, default_value = "3")]
+    pub retries: Option<u32>,
+
+    /// Base delay in milliseconds between retries
+    #[arg(long, default_value = "500")]
+    pub retry_delay_ms: Option<u64>,
+
+    /// Maximum duration of each HTTP request, and of the wait for MQTT acknowledgements, in
+    /// seconds
+    #[arg(long, default_value = "10")]
+    pub timeout: Option<u64>,
+
+    /// HTTP proxy used for all requests, overriding HTTP_PROXY / HTTPS_PROXY
+    #[arg(long, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// User-Agent sent with every request; met.no asks for one that identifies you, e.g. with a
+    /// contact address
+    #[arg(long)]
+    pub user_agent: Option<String>,
+
+    /// Only geocode locations in this country, as an ISO 3166-1 alpha-2 code (e.g. GB)
+    #[arg(long, value_name = "CODE", value_parser = parse_country_code)]
+    pub geocoding_country: Option<String>,
+
+    /// API key for geocode.maps.co, which throttles anonymous requests
+    #[arg(long, value_name = "KEY", env = "GEOCODING_API_KEY", hide_env_values = true)]
+    pub geocoding_api_key: Option<String>,
+
+    /// Use the N-th best geocoding match instead of the best one (1); with --batch-file, a
+    /// comma-separated list gives one per line of the file, for the place names among them
+    #[arg(long, value_name = "N", value_delimiter = ',')]
+    pub geocoding_index: Vec<usize>,
+
+    /// Save the resolved location under the given alias
+    #[arg(long, value_name = "ALIAS")]
+    pub save_location: Option<String>,
+
+    /// Read locations from a file, one `location` or `alias=location` per line, and output CSV;
+    /// the alias is separated by `=` rather than `,` since place names contain commas
+    #[arg(long, value_name = "PATH", conflicts_with = "location")]
+    pub batch_file: Option<PathBuf>,
+
+    /// Print the API URLs that would be requested, for each location of --batch-file if given,
+    /// and exit without fetching
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Print diagnostics to stderr, such as why a cached forecast was not used
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Maximum number of locations fetched at the same time in batch mode
+    #[arg(long, default_value = "4")]
+    pub concurrency: Option<usize>,
+
+    /// Also publish the current conditions to this MQTT broker, e.g. mqtt://localhost:1883
+    #[cfg(feature = "mqtt")]
+    #[arg(long, value_name = "URL")]
+    pub mqtt_broker: Option<String>,
+
+    /// Only print how long ago the forecast was updated, failing when older than --max-age
+    #[arg(long)]
+    pub since_update: bool,
+
+    /// Warn on stderr and exit with code 2 when this precipitation is forecast in the next 6
+    /// hours
+    #[arg(long, value_name = "TYPE")]
+    pub alert_precip_type: Option<PrecipitationAlert>,
+
+    /// Warn on stderr and exit with code 2 when thunder or heavy precipitation is forecast in
+    /// the next 6 hours
+    #[arg(long)]
+    pub alert_severe: bool,
+
+    /// Warn on stderr and exit with code 2 when fog is forecast in the next 12 hours
+    #[arg(long)]
+    pub alert_fog: bool,
+
+    /// Warn on stderr and exit with code 2 when the temperature drops below DEGREES, in the
+    /// --temp-unit, in the next 6 hours
+    #[arg(long, value_name = "DEGREES", allow_negative_numbers = true)]
+    pub alert_below_temp: Option<f64>,
+
+    /// Warn on stderr and exit with code 2 when the temperature rises above DEGREES, in the
+    /// --temp-unit, in the next 6 hours
+    #[arg(long, value_name = "DEGREES", allow_negative_numbers = true)]
+    pub alert_above_temp: Option<f64>,
+
+    /// Print the JSON Schema of the forecast data and exit
+    #[arg(long)]
+    pub print_schema: bool,
+
+    /// Print the last 10 searched locations and exit
+    #[arg(long)]
+    pub history: bool,
+
+    /// Delete the history of searched locations and exit
+    #[arg(long)]
+    pub clear_history: bool,
+
+    /// Show the forecast through $PAGER, or less when it is not set
+    #[arg(long, overrides_with = "no_pager")]
+    pub pager: bool,
+
+    /// Write the forecast straight to stdout, overriding --pager
+    #[arg(long)]
+    pub no_pager: bool,
+
+    /// Print the completion script for SHELL and exit
+    #[arg(long, value_name = "SHELL")]
+    pub generate_completion: Option<clap_complete::Shell>,
+}
+
+fn parse_country_code(code: &str) -> Result<String, String> {
+    match code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase()) {
+        true => Ok(code.to_string()),
+        false => Err(String::from(
+            "expected a two-letter uppercase ISO 3166-1 alpha-2 code, e.g. GB or AU",
+        )),
+    }
+}
+
+/// Range of `--width`
+const WIDTH_RANGE: std::ops::RangeInclusive<usize> = 40..=500;
+/// Range of `--retries`
+const RETRIES_RANGE: std::ops::RangeInclusive<u32> = 0..=10;
+/// Range of `--hours-ahead`
+const HOURS_AHEAD_RANGE: std::ops::RangeInclusive<u32> = 1..=240;
+/// Range of `--skip-hours`, as far ahead as `--hours-ahead` reaches
+const SKIP_HOURS_RANGE: std::ops::RangeInclusive<u32> = 0..=240;
+/// Range of `--max-age`, in minutes; met.no updates its forecasts every hour, so a week is plenty
+const MAX_AGE_RANGE: std::ops::RangeInclusive<u64> = 1..=7 * 24 * 60;
+/// Range of `--geocoding-index`, as the geocoder returns at most 10 matches
+const GEOCODING_INDEX_RANGE: std::ops::RangeInclusive<usize> = 1..=10;
+
+impl WeatherArgs {
+    /// Checks the constraints between arguments that clap cannot express, returning every
+    /// violated one
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.start_date.is_some() != self.end_date.is_some() {
+            errors.push(String::from("--start-date and --end-date must be given together"));
+        }
+        if let (Some(below), Some(above)) = (self.alert_below_temp, self.alert_above_temp) {
+            if below >= above {
+                errors.push(format!(
+                    "--alert-below-temp must be lower than --alert-above-temp, got {} and {}",
+                    below, above
+                ));
+            }
+        }
+        if let Some(width) = self.width {
+            if !WIDTH_RANGE.contains(&width) {
+                errors.push(format!(
+                    "--width must be between {} and {}, got {}",
+                    WIDTH_RANGE.start(),
+                    WIDTH_RANGE.end(),
+                    width
+                ));
+            }
+        }
+        if let Some(retries) = self.retries {
+            if !RETRIES_RANGE.contains(&retries) {
+                errors.push(format!(
+                    "--retries must be between {} and {}, got {}",
+                    RETRIES_RANGE.start(),
+                    RETRIES_RANGE.end(),
+                    retries
+                ));
+            }
+        }
+        if let Some(hours) = self.hours_ahead {
+            if !HOURS_AHEAD_RANGE.contains(&hours) {
+                errors.push(format!(
+                    "--hours-ahead must be between {} and {}, got {}",
+                    HOURS_AHEAD_RANGE.start(),
+                    HOURS_AHEAD_RANGE.end(),
+                    hours
+                ));
+            }
+        }
+        if let Some(hours) = self.skip_hours {
+            if !SKIP_HOURS_RANGE.contains(&hours) {
+                errors.push(format!(
+                    "--skip-hours must be between {} and {}, got {}",
+                    SKIP_HOURS_RANGE.start(),
+                    SKIP_HOURS_RANGE.end(),
+                    hours
+                ));
+            }
+        }
+        if let Some(minutes) = self.max_age {
+            if !MAX_AGE_RANGE.contains(&minutes) {
+                errors.push(format!(
+                    "--max-age must be between {} and {}, got {}",
+                    MAX_AGE_RANGE.start(),
+                    MAX_AGE_RANGE.end(),
+                    minutes
+                ));
+            }
+        }
+        for index in &self.geocoding_index {
+            if !GEOCODING_INDEX_RANGE.contains(index) {
+                errors.push(format!(
+                    "--geocoding-index must be between {} and {}, got {}",
+                    GEOCODING_INDEX_RANGE.start(),
+                    GEOCODING_INDEX_RANGE.end(),
+                    index
+                ));
+            }
+        }
+        if self.batch_file.is_none() && self.geocoding_index.len() > 1 {
+            errors.push(String::from(
+                "--geocoding-index takes a single index unless --batch-file is given",
+            ));
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+
+    /// The --duration, unless --hours-ahead or both --start-date and --end-date are given
+    pub fn duration(&self) -> DurationType {
+        if let Some(hours) = self.hours_ahead {
+            return DurationType::HoursAhead(hours);
+        }
+        match (self.start_date, self.end_date) {
+            (Some(start), Some(end)) => DurationType::Custom { start, end },
+            _ => self.duration.unwrap(),
+        }
+    }
+
+    /// The --max-age as a duration, the longest one chrono represents when it does not fit
+    pub fn max_age(&self) -> Option<chrono::Duration> {
+        self.max_age.map(|minutes| {
+            i64::try_from(minutes)
+                .ok()
+                .and_then(chrono::Duration::try_minutes)
+                .unwrap_or(chrono::Duration::MAX)
+        })
+    }
+
+    /// Position in the geocoding matches sorted by importance to use for the `line`-th location,
+    /// 0 unless --geocoding-index gives one
+    pub fn geocoding_index(&self, line: usize) -> usize {
+        self.geocoding_index.get(line).map_or(0, |index| index - 1)
+    }
+
+    pub fn unit_system(&self) -> UnitSystem {
+        match self.imperial {
+            true => UnitSystem::Imperial,
+            false => UnitSystem::Metric,
+        }
+    }
+
+    pub fn builder() -> WeatherArgsBuilder {
+        WeatherArgsBuilder::default()
+    }
+}
+
+/// Builds `WeatherArgs` for library use without parsing a command line or reading the
+/// environment; anything not set gets the default of the matching command line option
+///
+/// ```no_run
+/// use weather_cli::args::{DurationType, WeatherArgs};
+///
+/// let args = WeatherArgs::builder()
+///     .location("Oslo")
+///     .duration(DurationType::Today)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeatherArgsBuilder {
+    location: Option<String>,
+    duration: DurationType,
+    output_mode: OutputMode,
+    temp_unit: Option<TempUnit>,
+    pressure_unit: Option<PressureUnit>,
+    speed_unit: Option<SpeedUnit>,
+    timezone: Option<chrono_tz::Tz>,
+    feels_like: bool,
+    no_emoji: bool,
+    columns: Vec<ColumnField>,
+    width: Option<usize>,
+    retries: u32,
+    timeout: u64,
+    user_agent: Option<String>,
+    geocoding_country: Option<String>,
+    geocoding_api_key: Option<String>,
+}
+
+impl Default for WeatherArgsBuilder {
+    fn default() -> Self {
+        WeatherArgsBuilder {
+            location: None,
+            duration: DurationType::Now,
+            output_mode: OutputMode::Compact,
+            temp_unit: None,
+            pressure_unit: None,
+            speed_unit: None,
+            timezone: None,
+            feels_like: false,
+            no_emoji: false,
+            columns: Vec::new(),
+            width: None,
+            retries: 3,
+            timeout: 10,
+            user_agent: None,
+            geocoding_country: None,
+            geocoding_api_key: None,
+        }
+    }
+}
+
+impl WeatherArgsBuilder {
+    /// Place name, saved alias or `lat,lon` coordinates; required
+    pub fn location(mut self, location: impl Into<String>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    pub fn duration(mut self, duration: DurationType) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn output_mode(mut self, output_mode: OutputMode) -> Self {
+        self.output_mode = output_mode;
+        self
+    }
+
+    pub fn temp_unit(mut self, temp_unit: TempUnit) -> Self {
+        self.temp_unit = Some(temp_unit);
+        self
+    }
+
+    pub fn pressure_unit(mut self, pressure_unit: PressureUnit) -> Self {
+        self.pressure_unit = Some(pressure_unit);
+        self
+    }
+
+    pub fn speed_unit(mut self, speed_unit: SpeedUnit) -> Self {
+        self.speed_unit = Some(speed_unit);
+        self
+    }
+
+    pub fn timezone(mut self, timezone: chrono_tz::Tz) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    pub fn feels_like(mut self, feels_like: bool) -> Self {
+        self.feels_like = feels_like;
+        self
+    }
+
+    pub fn no_emoji(mut self, no_emoji: bool) -> Self {
+        self.no_emoji = no_emoji;
+        self
+    }
+
+    pub fn columns(mut self, columns: Vec<ColumnField>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Maximum duration of each HTTP request, in seconds
+    pub fn timeout(mut self, timeout: u64) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn geocoding_country(mut self, country: impl Into<String>) -> Self {
+        self.geocoding_country = Some(country.into());
+        self
+    }
+
+    pub fn geocoding_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.geocoding_api_key = Some(api_key.into());
+        self
+    }
+
+    /// The arguments, or every problem with them joined by `; `
+    pub fn build(self) -> Result<WeatherArgs, String> {
+        let location = self.location.ok_or("a location is required")?;
+        if let Some(country) = &self.geocoding_country {
+            parse_country_code(country)?;
+        }
+        // --start-date and --end-date cannot be set here, so a custom range is given through
+        // the duration
+        let (duration, start_date, end_date, hours_ahead) = match self.duration {
+            DurationType::Custom { start, end } => {
+                (DurationType::Now, Some(start), Some(end), None)
+            }
+            DurationType::HoursAhead(hours) => (DurationType::Now, None, None, Some(hours)),
+            duration => (duration, None, None, None),
+        };
+
+        let args = WeatherArgs {
+            command: None,
+            location: Some(location),
+            duration: Some(duration),
+            start_date,
+            end_date,
+            hours_ahead,
+            skip_hours: None,
+            timezone: self.timezone,
+            output_mode: Some(self.output_mode),
+            temp_unit: self.temp_unit,
+            pressure_unit: self.pressure_unit,
+            speed_unit: self.speed_unit,
+            metric: false,
+            imperial: false,
+            color_theme: Some(ColorTheme::Auto),
+            feels_like: self.feels_like,
+            no_emoji: self.no_emoji,
+            show_units: false,
+            max_age: None,
+            columns: self.columns,
+            width: self.width,
+            retries: Some(self.retries),
+            retry_delay_ms: Some(500),
+            timeout: Some(self.timeout),
+            proxy: None,
+            user_agent: self.user_agent,
+            geocoding_country: self.geocoding_country,
+            geocoding_api_key: self.geocoding_api_key,
+            geocoding_index: Vec::new(),
+            save_location: None,
+            batch_file: None,
+            dry_run: false,
+            verbose: false,
+            concurrency: Some(4),
+            #[cfg(feature = "mqtt")]
+            mqtt_broker: None,
+            since_update: false,
+            alert_precip_type: None,
+            alert_severe: false,
+            alert_fog: false,
+            alert_below_temp: None,
+            alert_above_temp: None,
+            print_schema: false,
+            history: false,
+            clear_history: false,
+            pager: false,
+            no_pager: false,
+            generate_completion: None,
+        };
+        args.validate().map_err(|errors| errors.join("; "))?;
+        Ok(args)
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Manage saved locations
+    Locations {
+        #[command(subcommand)]
+        action: LocationsAction,
+    },
+    /// Serve forecasts over HTTP, as JSON at /weather?location=<location>
+    #[cfg(feature = "serve")]
+    Serve(ServeArgs),
+}
+
+#[cfg(feature = "serve")]
+#[derive(clap::Args, Debug, Clone)]
+pub struct ServeArgs {
+    /// Address the servers listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    pub address: IpAddr,
+    /// Port of the HTTP server
+    #[arg(long, default_value = "8080")]
+    pub port: u16,
+    /// Also serve the `weather.Weather` gRPC service of weather.proto
+    #[cfg(feature = "grpc")]
+    #[arg(long)]
+    pub grpc: bool,
+    /// Port of the gRPC server
+    #[cfg(feature = "grpc")]
+    #[arg(long, default_value = "50051")]
+    pub grpc_port: u16,
+    /// Also push forecast updates to WebSocket clients subscribed to their location
+    #[cfg(feature = "websocket")]
+    #[arg(long)]
+    pub websocket: bool,
+    /// Port of the WebSocket server
+    #[cfg(feature = "websocket")]
+    #[arg(long, default_value = "8081")]
+    pub websocket_port: u16,
+    /// Minutes between refreshes of the forecasts WebSocket clients are subscribed to
+    #[cfg(feature = "websocket")]
+    #[arg(long, default_value = "30")]
+    pub refresh_minutes: u64,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum LocationsAction {
+    /// List all saved locations
+    List,
+    /// Remove a saved location
+    Remove { alias: String },
+    /// Show the coordinates of a saved location
+    Show { alias: String },
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
 pub enum DurationType {
     Now,
+    /// The hour starting closest to an hour from now
+    NextHour,
     Today,
     Tomorrow,
     Week,
+    /// The coming Saturday and Sunday, or the current weekend on Saturdays and Sundays
+    Weekend,
+    /// 06:00 to 12:00 today
+    Morning,
+    /// 12:00 to 18:00 today
+    Afternoon,
+    /// 18:00 to 22:00 today
+    Evening,
+    /// 22:00 today to 06:00 tomorrow
+    Night,
+    /// Every day from `start` to `end`, given with --start-date and --end-date
+    #[value(skip)]
+    Custom { start: NaiveDate, end: NaiveDate },
+    /// From the current hour to the given number of hours ahead, given with --hours-ahead
+    #[value(skip)]
+    HoursAhead(u32),
 }
 
 #[derive(Debug)]
@@ -43,6 +679,189 @@ pub enum OutputMode {
     Compact,
     Detailed,
     Complete,
+    /// Current conditions on a single line, for scripts
+    Minimal,
+    /// Pandoc Markdown that converts cleanly to EPUB
+    KindleMd,
+    /// Ledger / hledger comments to paste into a journal
+    LedgerComment,
+    /// Aligned table with a header row
+    Table,
+    /// Org-mode note for Orgzly
+    Orgzly,
+    /// Obsidian note with the summary in its frontmatter
+    Obsidian,
+    /// Markdown for Notion's importer, with current conditions as a callout
+    Notion,
+    /// Logseq outline with a block per forecast time
+    Logseq,
+    /// Roam Research block with the current conditions and tags
+    Roam,
+    /// Dendron note named after the location and date
+    Dendron,
+    /// Current conditions as Prometheus metrics
+    Prometheus,
+    /// Conky text with the current conditions and the next hours, for `execpi`
+    Conky,
+    /// Short tmux status-line segment colored by the temperature
+    Tmux,
+    /// Single Xmobar line with the temperature colored by `<fc>` tags
+    Xmobar,
+    /// Today and tomorrow side by side
+    Compare,
+    /// CSV written through a Polars DataFrame
+    #[cfg(feature = "polars")]
+    PolarsCsv,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug, Default)]
+pub enum TempUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug, Default)]
+pub enum PressureUnit {
+    #[default]
+    #[value(alias = "hpa")]
+    Hectopascal,
+    #[value(alias = "mbar")]
+    Millibar,
+    #[value(alias = "inhg")]
+    InchesOfMercury,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug, Default)]
+pub enum SpeedUnit {
+    #[default]
+    #[value(alias = "mps")]
+    MetersPerSecond,
+    #[value(alias = "kmh")]
+    KilometersPerHour,
+    #[value(alias = "mph")]
+    MilesPerHour,
+    #[value(alias = "kn")]
+    Knots,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug, Default)]
+pub enum ColorTheme {
+    Dark,
+    Light,
+    /// Guess from the COLORFGBG and TERM_PROGRAM environment variables
+    #[default]
+    Auto,
+}
+
+/// Units chosen together by `--metric` / `--imperial`
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl UnitSystem {
+    pub fn temp_unit(self) -> TempUnit {
+        match self {
+            UnitSystem::Metric => TempUnit::Celsius,
+            UnitSystem::Imperial => TempUnit::Fahrenheit,
+        }
+    }
+
+    pub fn pressure_unit(self) -> PressureUnit {
+        match self {
+            UnitSystem::Metric => PressureUnit::Hectopascal,
+            UnitSystem::Imperial => PressureUnit::InchesOfMercury,
+        }
+    }
+
+    pub fn speed_unit(self) -> SpeedUnit {
+        match self {
+            UnitSystem::Metric => SpeedUnit::MetersPerSecond,
+            UnitSystem::Imperial => SpeedUnit::MilesPerHour,
+        }
+    }
+}
+
+/// Precipitation watched by `--alert-precip-type`
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum PrecipitationAlert {
+    Rain,
+    Sleet,
+    Snow,
+    /// Rain, sleet or snow
+    Any,
+}
+
+impl PrecipitationAlert {
+    pub fn matches(self, precipitation: PrecipitationType) -> bool {
+        match self {
+            PrecipitationAlert::Rain => precipitation == PrecipitationType::Rain,
+            PrecipitationAlert::Sleet => precipitation == PrecipitationType::Sleet,
+            PrecipitationAlert::Snow => precipitation == PrecipitationType::Snow,
+            PrecipitationAlert::Any => precipitation != PrecipitationType::None,
+        }
+    }
+}
+
+/// Field that can be selected with `--columns`, in the order of the CSV columns
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+#[value(rename_all = "snake_case")]
+pub enum ColumnField {
+    Weather,
+    Temperature,
+    WindSpeed,
+    WindDirection,
+    Humidity,
+    CloudCover,
+    Pressure,
+    Precipitation,
 }
 
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::*;
+    use crate::weather::DisplayOptions;
+
+    #[test]
+    fn skip_hours_past_the_range_are_rejected() {
+        let args = WeatherArgs::builder().location("Oslo").build().unwrap();
+        let args = WeatherArgs {
+            skip_hours: Some(u32::MAX),
+            ..args
+        };
+        assert_eq!(
+            args.validate().unwrap_err(),
+            ["--skip-hours must be between 0 and 240, got 4294967295"]
+        );
+        // past the latest date of chrono, without panicking
+        assert_eq!(DisplayOptions::from(&args).now(), DateTime::<Utc>::MAX_UTC);
+    }
 
+    #[test]
+    fn max_age_is_minutes_up_to_a_week() {
+        let args = WeatherArgs::builder().location("Oslo").build().unwrap();
+        assert_eq!(args.max_age(), None);
+
+        let args = WeatherArgs {
+            max_age: Some(90),
+            ..args
+        };
+        assert!(args.validate().is_ok());
+        assert_eq!(args.max_age(), Some(Duration::minutes(90)));
+
+        let args = WeatherArgs {
+            max_age: Some(u64::MAX),
+            ..args
+        };
+        assert_eq!(
+            args.validate().unwrap_err(),
+            ["--max-age must be between 1 and 10080, got 18446744073709551615"]
+        );
+        assert_eq!(args.max_age(), Some(Duration::MAX));
+    }
+}
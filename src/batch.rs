@@ -0,0 +1,267 @@
+use rayon::prelude::*;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::args::{DurationType, WeatherArgs};
+use crate::error::AppError;
+use crate::geocoding::geocode_batch;
+use crate::http::{build_client, run_concurrently};
+use crate::locations::{resolve_without_geocoding, SavedLocation, SavedLocations};
+use crate::weather::{
+    csv_header, get_weather_data_with_client, DisplayOptions, WeatherData, WeatherError,
+};
+
+pub struct WeatherReport {
+    pub location: String,
+    pub display_name: String,
+    pub data: WeatherData,
+}
+
+/// A line of the batch file, either `location` or `alias=location`; place names often contain
+/// commas, e.g. `London, UK`, but not `=`
+struct BatchEntry {
+    label: String,
+    query: String,
+}
+
+fn parse_batch_file(contents: &str) -> Vec<BatchEntry> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.split_once('=') {
+            Some((alias, location)) => BatchEntry {
+                label: alias.trim().to_string(),
+                query: location.trim().to_string(),
+            },
+            _ => BatchEntry {
+                label: line.to_string(),
+                query: line.to_string(),
+            },
+        })
+        .collect()
+}
+
+/// Places of the batch file, in the order of the file
+pub fn read_batch_queries(path: &Path) -> Result<Vec<String>, AppError> {
+    let entries = parse_batch_file(&std::fs::read_to_string(path)?);
+    Ok(entries.into_iter().map(|entry| entry.query).collect())
+}
+
+async fn fetch_report(
+    client: &reqwest::Client,
+    entry: BatchEntry,
+    location: SavedLocation,
+    args: &WeatherArgs,
+) -> Result<WeatherReport, AppError> {
+    let data = get_weather_data_with_client(client, (location.lat, location.lon), args)
+        .await
+        .map_err(|e| AppError::weather(e, args))?
+        .ok_or(AppError::Weather(WeatherError::MissingData))?;
+
+    if let Some(max_age) = args.max_age() {
+        data.check_age(max_age)?;
+    }
+    if let DurationType::Custom { start, end } = args.duration() {
+        data.check_date_range(start, end)?;
+    }
+
+    Ok(WeatherReport {
+        location: entry.label,
+        display_name: location.display_name,
+        data,
+    })
+}
+
+/// Fetches the weather for every location in the batch file, keeping the order of the file
+pub async fn fetch_batch(
+    path: &Path,
+    saved_locations: SavedLocations,
+    args: Arc<WeatherArgs>,
+) -> Result<Vec<(String, Result<WeatherReport, AppError>)>, AppError> {
+    let entries = parse_batch_file(&tokio::fs::read_to_string(path).await?);
+    let client = build_client(&args).map_err(|e| AppError::network(e, &args))?;
+    let concurrency = args.concurrency.unwrap();
+
+    // coordinates and saved aliases need no request, the remaining places are geocoded together
+    let resolved: Vec<_> = entries
+        .iter()
+        .map(|entry| resolve_without_geocoding(&entry.query, &saved_locations))
+        .collect();
+    let (queries, indices) = entries
+        .iter()
+        .zip(&resolved)
+        .enumerate()
+        .filter(|(_, (_, location))| location.is_none())
+        .map(|(line, (entry, _))| (entry.query.clone(), args.geocoding_index(line)))
+        .unzip();
+    let mut geocoded = geocode_batch(queries, &client, concurrency, indices, Arc::clone(&args))
+        .await
+        .into_iter();
+    let locations: Vec<_> = resolved
+        .into_iter()
+        .map(|location| {
+            location.unwrap_or_else(|| {
+                let data = geocoded.next().expect("a geocoding result for every query");
+                data.map(|data| SavedLocation::from(&data))
+            })
+        })
+        .collect();
+
+    let jobs = entries.into_iter().zip(locations).collect();
+    Ok(run_concurrently(jobs, concurrency, |(entry, location)| {
+        let client = client.clone();
+        let args = Arc::clone(&args);
+        async move {
+            let label = entry.label.clone();
+            let report = match location {
+                Ok(location) => fetch_report(&client, entry, location, &args).await,
+                Err(e) => Err(e),
+            };
+            (label, report)
+        }
+    })
+    .await)
+}
+
+/// Combined CSV for all reports, with failed locations reported in the `warning` column
+pub fn format_batch_csv(
+    results: &[(String, Result<WeatherReport, AppError>)],
+    args: &WeatherArgs,
+) -> String {
+    let now = DisplayOptions::from(args).now();
+    let header = csv_header(&args.columns);
+    let empty_columns = ",".repeat(header.matches(',').count() + 1);
+    let mut output = format!("location,{},warning\n", header);
+
+    for (label, result) in results {
+        match result {
+            Ok(report) => {
+                for row in report
+                    .data
+                    .csv_rows(args.duration(), now, &args.columns)
+                {
+                    output.push_str(&format!("{},{},\n", csv_field(label), row));
+                }
+            }
+            Err(e) => output.push_str(&format!(
+                "{}{},{}\n",
+                csv_field(label),
+                empty_columns,
+                csv_field(&e.to_string())
+            )),
+        }
+    }
+    output
+}
+
+/// Formats every report with the output mode of `args`, spreading the work across threads
+pub fn parallel_format(reports: &[WeatherReport], args: &WeatherArgs) -> Vec<String> {
+    reports
+        .par_iter()
+        .map(|report| {
+            report
+                .data
+                .format(
+                    args.duration(),
+                    report.display_name.clone(),
+                    args.output_mode.unwrap(),
+                    &DisplayOptions::from(args),
+                )
+                .unwrap_or_else(|e| format!("{}: {}", report.location, e))
+        })
+        .collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, DurationRound, Utc};
+    use serde_json::json;
+
+    use super::*;
+
+    /// Report with hourly entries from the hour before now for the next two days
+    fn report(location: &str) -> WeatherReport {
+        let hour = Utc::now().duration_trunc(Duration::hours(1)).unwrap();
+        let timeseries: Vec<_> = (-1..48)
+            .map(|offset| {
+                json!({
+                    "time": hour + Duration::hours(offset),
+                    "data": {
+                        "instant": { "details": { "air_temperature": offset as f64 } },
+                        "next_1_hours": { "summary": { "symbol_code": "cloudy" } },
+                    },
+                })
+            })
+            .collect();
+        let data = serde_json::from_value(json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [10.75, 59.91, 0] },
+            "properties": {
+                "meta": { "updated_at": hour, "units": {} },
+                "timeseries": timeseries,
+            },
+        }))
+        .unwrap();
+        WeatherReport {
+            location: location.to_string(),
+            display_name: location.to_string(),
+            data,
+        }
+    }
+
+    /// Times of the rows of a batch CSV
+    fn row_times(csv: &str) -> Vec<DateTime<Utc>> {
+        csv.lines()
+            .skip(1)
+            .map(|row| row.split(',').nth(1).unwrap().parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn only_an_equals_sign_separates_an_alias() {
+        let entries = parse_batch_file("London, UK\nhome = Paris, France\n\n59.91,10.75\n");
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.label.as_str(), entry.query.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("London, UK", "London, UK"),
+                ("home", "Paris, France"),
+                ("59.91,10.75", "59.91,10.75"),
+            ]
+        );
+    }
+
+    #[test]
+    fn batch_csv_skips_the_skip_hours() {
+        let results = vec![(String::from("Oslo"), Ok(report("Oslo")))];
+        let args = WeatherArgs::builder()
+            .location("Oslo")
+            .duration(DurationType::Now)
+            .build()
+            .unwrap();
+        let now = Utc::now();
+        let times = row_times(&format_batch_csv(&results, &args));
+        assert_eq!(times.len(), 1);
+        assert!(times[0] <= now);
+
+        let args = WeatherArgs {
+            skip_hours: Some(24),
+            ..args
+        };
+        let times = row_times(&format_batch_csv(&results, &args));
+        assert_eq!(times.len(), 1);
+        assert!(times[0] > now + Duration::hours(23));
+    }
+}
@@ -0,0 +1,202 @@
+//! Forecasts of recent runs, reused for a few minutes to spare the met.no API
+
+use std::fmt::{self, Display};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::args::WeatherArgs;
+use crate::weather::{get_weather_data_with_client, WeatherData, WeatherError};
+
+/// How long a cached forecast is shown instead of fetching it again
+const MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Forecasts stored in `~/.cache/weather-cli`, one `<lat>,<lon>.json` per location with the
+/// `WeatherData::checksum` of its contents next to it in `<lat>,<lon>.checksum`
+pub struct ForecastCache {
+    dir: PathBuf,
+    max_age: Duration,
+}
+
+/// Why a cached forecast was not used
+#[derive(Debug)]
+pub enum CacheMiss {
+    Missing,
+    Expired,
+    /// The forecast does not parse or does not match its checksum
+    Corrupted,
+    Io(std::io::Error),
+}
+
+impl Display for CacheMiss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheMiss::Missing => write!(f, "No cached forecast, fetching"),
+            CacheMiss::Expired => write!(f, "Cached forecast expired, refetching"),
+            CacheMiss::Corrupted => write!(f, "Cache corrupted, refetching"),
+            CacheMiss::Io(e) => write!(f, "Could not read the cache, fetching: {}", e),
+        }
+    }
+}
+
+impl ForecastCache {
+    /// The cache in the user's cache directory, `None` when there is none
+    pub fn open() -> Option<ForecastCache> {
+        dirs::cache_dir().map(|dir| ForecastCache {
+            dir: dir.join("weather-cli"),
+            max_age: MAX_AGE,
+        })
+    }
+
+    fn paths(&self, coords: (f64, f64)) -> (PathBuf, PathBuf) {
+        // met.no rounds coordinates to 4 decimals too
+        let name = format!("{:.4},{:.4}", coords.0, coords.1);
+        (
+            self.dir.join(format!("{}.json", name)),
+            self.dir.join(format!("{}.checksum", name)),
+        )
+    }
+
+    /// The forecast cached for `coords`, if it is recent and intact
+    pub fn load(&self, coords: (f64, f64)) -> Result<WeatherData, CacheMiss> {
+        let (forecast_path, checksum_path) = self.paths(coords);
+        let modified = match std::fs::metadata(&forecast_path).and_then(|meta| meta.modified()) {
+            Ok(modified) => modified,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(CacheMiss::Missing),
+            Err(e) => return Err(CacheMiss::Io(e)),
+        };
+        // a modification time in the future counts as fresh
+        let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+        if age > self.max_age {
+            return Err(CacheMiss::Expired);
+        }
+
+        let contents = std::fs::read(&forecast_path).map_err(CacheMiss::Io)?;
+        let checksum = match std::fs::read_to_string(&checksum_path) {
+            Ok(checksum) => u64::from_str_radix(checksum.trim(), 16).ok(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(CacheMiss::Io(e)),
+        };
+        let weather_data: WeatherData =
+            serde_json::from_slice(&contents).map_err(|_| CacheMiss::Corrupted)?;
+        match checksum == Some(weather_data.checksum()) {
+            true => Ok(weather_data),
+            false => Err(CacheMiss::Corrupted),
+        }
+    }
+
+    /// Caches `weather_data` for `coords`, replacing the forecast cached before
+    pub fn store(&self, coords: (f64, f64), weather_data: &WeatherData) -> std::io::Result<()> {
+        let (forecast_path, checksum_path) = self.paths(coords);
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(
+            forecast_path,
+            serde_json::to_vec(weather_data).expect("forecast serializes to JSON"),
+        )?;
+        std::fs::write(checksum_path, format!("{:016x}\n", weather_data.checksum()))
+    }
+}
+
+/// Forecast for `coords` from the cache when it has a recent and intact one, otherwise from
+/// met.no, caching what it fetched; with `--verbose`, says on stderr why the cache was not used
+pub async fn get_weather_data_cached(
+    client: &reqwest::Client,
+    coords: (f64, f64),
+    args: &WeatherArgs,
+) -> Result<Option<WeatherData>, WeatherError> {
+    let cache = ForecastCache::open();
+    if let Some(cache) = &cache {
+        match cache.load(coords) {
+            Ok(weather_data) => return Ok(Some(weather_data)),
+            Err(miss) if args.verbose => eprintln!("{}", miss),
+            Err(_) => {}
+        }
+    }
+
+    let weather_data = get_weather_data_with_client(client, coords, args).await?;
+    if let (Some(cache), Some(weather_data)) = (&cache, &weather_data) {
+        if let Err(e) = cache.store(coords, weather_data) {
+            if args.verbose {
+                eprintln!("Could not cache the forecast: {}", e);
+            }
+        }
+    }
+    Ok(weather_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const OSLO: (f64, f64) = (59.91, 10.75);
+
+    /// Cache in a directory of its own under the system temporary directory
+    fn cache(name: &str) -> ForecastCache {
+        let dir = std::env::temp_dir()
+            .join(format!("weather-cli-cache-{}", std::process::id()))
+            .join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        ForecastCache {
+            dir,
+            max_age: MAX_AGE,
+        }
+    }
+
+    fn weather_data() -> WeatherData {
+        serde_json::from_value(json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [10.75, 59.91, 0] },
+            "properties": {
+                "meta": { "updated_at": "2026-10-16T00:00:00Z", "units": {} },
+                "timeseries": [{
+                    "time": "2026-10-16T00:00:00Z",
+                    "data": { "instant": { "details": { "air_temperature": 5.0 } } },
+                }],
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn stored_forecasts_load_until_they_expire() {
+        let cache = cache("fresh");
+        assert!(matches!(cache.load(OSLO), Err(CacheMiss::Missing)));
+
+        cache.store(OSLO, &weather_data()).unwrap();
+        let loaded = cache.load(OSLO).unwrap();
+        assert_eq!(loaded.checksum(), weather_data().checksum());
+        assert!(matches!(cache.load((59.0, 10.0)), Err(CacheMiss::Missing)));
+
+        let cache = ForecastCache {
+            max_age: Duration::ZERO,
+            ..cache
+        };
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(matches!(cache.load(OSLO), Err(CacheMiss::Expired)));
+    }
+
+    #[test]
+    fn forecasts_not_matching_their_checksum_are_corrupted() {
+        let cache = cache("corrupted");
+        cache.store(OSLO, &weather_data()).unwrap();
+        let (forecast_path, checksum_path) = cache.paths(OSLO);
+
+        let contents = std::fs::read_to_string(&forecast_path).unwrap();
+        std::fs::write(&forecast_path, contents.replace("5.0", "6.0")).unwrap();
+        match cache.load(OSLO) {
+            Err(miss @ CacheMiss::Corrupted) => {
+                assert_eq!(miss.to_string(), "Cache corrupted, refetching")
+            }
+            _ => panic!("a changed forecast is corrupted"),
+        }
+
+        std::fs::write(&forecast_path, &contents[..contents.len() / 2]).unwrap();
+        assert!(matches!(cache.load(OSLO), Err(CacheMiss::Corrupted)));
+
+        std::fs::write(&forecast_path, contents).unwrap();
+        assert!(cache.load(OSLO).is_ok());
+        std::fs::remove_file(checksum_path).unwrap();
+        assert!(matches!(cache.load(OSLO), Err(CacheMiss::Corrupted)));
+    }
+}
@@ -0,0 +1,166 @@
+use chrono::{DateTime, FixedOffset};
+use unicode_width::UnicodeWidthStr;
+
+/// Block characters from empty to full, one per eighth of the height
+const BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One-line bar chart of precipitation amounts in mm, no wider than `width`, with the time of
+/// the first and last slot below it
+///
+/// Every slot gets the same number of columns; slots that do not fit into `width` are left out.
+pub fn precipitation_bar_chart(series: &[(DateTime<FixedOffset>, f64)], width: usize) -> String {
+    let (Some((first, _)), Some((last, _))) = (series.first(), series.last()) else {
+        return String::new();
+    };
+    let max = series.iter().map(|(_, amount)| *amount).fold(0.0, f64::max);
+    let slot_width = (width / series.len()).max(1);
+
+    let bars: String = series
+        .iter()
+        .take(width / slot_width)
+        .flat_map(|(_, amount)| {
+            let level = match max > 0.0 {
+                true => (amount / max * 8.0).round() as usize,
+                false => 0,
+            };
+            std::iter::repeat_n(BLOCKS[level.min(8)], slot_width)
+        })
+        .collect();
+
+    let start = first.format("%H:%M").to_string();
+    let end = last.format("%H:%M").to_string();
+    let gap = bars
+        .chars()
+        .count()
+        .saturating_sub(start.width() + end.width())
+        .max(1);
+    format!(
+        "Precipitation (max {} mm)\n{}\n{}{}{}\n",
+        max,
+        bars,
+        start,
+        " ".repeat(gap),
+        end
+    )
+}
+
+/// Compass sectors of `wind_rose_ascii`, clockwise from north, with the row and column step of
+/// their arm and the character it is drawn with
+const ROSE_SECTORS: [(&str, isize, isize, char); 8] = [
+    ("N", -1, 0, '|'),
+    ("NE", -1, 2, '/'),
+    ("E", 0, 2, '-'),
+    ("SE", 1, 2, '\\'),
+    ("S", 1, 0, '|'),
+    ("SW", 1, -2, '/'),
+    ("W", 0, -2, '-'),
+    ("NW", -1, -2, '\\'),
+];
+
+/// Longest arm of `wind_rose_ascii`, in rows
+const MAX_ROSE_RADIUS: usize = 6;
+
+/// Wind rose of `(speed, direction)` pairs, with an arm per compass sector the wind blows from
+/// whose length is proportional to how often it does, no wider than `width`; `unit` is the
+/// unit of the speeds
+///
+/// Arms take two columns per row so that the rose looks round in a terminal.
+pub fn wind_rose_ascii(series: &[(f64, f64)], unit: &str, width: usize) -> String {
+    // an arm of `radius` rows and its label take `2 * radius + 3` columns on each side
+    let radius = (width.saturating_sub(7) / 4).min(MAX_ROSE_RADIUS);
+    if series.is_empty() || radius == 0 {
+        return String::new();
+    }
+
+    let mut counts = [0usize; 8];
+    for (_, direction) in series {
+        counts[((direction.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8] += 1;
+    }
+    let max = *counts.iter().max().unwrap_or(&1);
+
+    let (rows, columns) = (2 * radius + 3, 4 * radius + 7);
+    let (center_row, center_column) = (radius + 1, 2 * radius + 3);
+    let mut grid = vec![vec![' '; columns]; rows];
+    grid[center_row][center_column] = '+';
+
+    for ((label, row_step, column_step, arm), count) in ROSE_SECTORS.iter().zip(counts) {
+        if count == 0 {
+            continue;
+        }
+        let length = ((count * radius) as f64 / max as f64).round().max(1.0) as isize;
+        let position = |step: isize| {
+            (
+                (center_row as isize + row_step * step) as usize,
+                (center_column as isize + column_step * step) as usize,
+            )
+        };
+        for step in 1..=length {
+            let (row, column) = position(step);
+            grid[row][column] = *arm;
+            // horizontal arms are continuous lines
+            if *row_step == 0 {
+                grid[row][(column as isize - column_step / 2) as usize] = *arm;
+            }
+        }
+        // labels on the west side end at the arm instead of starting there
+        let (row, column) = position(length + 1);
+        let start = match *column_step < 0 {
+            true => column + 1 - label.len(),
+            false => column,
+        };
+        for (offset, c) in label.chars().enumerate() {
+            grid[row][start + offset] = c;
+        }
+    }
+
+    let mean_speed = series.iter().map(|(speed, _)| speed).sum::<f64>() / series.len() as f64;
+    let lines: Vec<String> = grid
+        .into_iter()
+        .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
+        .collect();
+    // the rows of the labels stay empty when the vertical arms are short
+    let first = lines.iter().position(|line| !line.is_empty()).unwrap_or(0);
+    let last = lines.iter().rposition(|line| !line.is_empty()).unwrap_or(0);
+
+    let mut output = format!("Wind rose (mean speed {:.1} {})\n", mean_speed, unit);
+    for line in &lines[first..=last] {
+        output.push_str(line);
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precipitation_bars_are_scaled_to_the_maximum() {
+        let start = DateTime::parse_from_rfc3339("2026-10-16T00:00:00+02:00").unwrap();
+        let series: Vec<_> = [0.0, 1.0, 2.0]
+            .into_iter()
+            .zip(0..)
+            .map(|(amount, hour)| (start + chrono::Duration::hours(hour), amount))
+            .collect();
+        assert_eq!(
+            precipitation_bar_chart(&series, 7),
+            "Precipitation (max 2 mm)\n  ▄▄██\n00:00 02:00\n"
+        );
+        assert_eq!(
+            precipitation_bar_chart(&series[..1], 3),
+            "Precipitation (max 0 mm)\n   \n00:00 00:00\n"
+        );
+        assert_eq!(precipitation_bar_chart(&[], 10), "");
+    }
+
+    #[test]
+    fn wind_rose_has_an_arm_per_wind_direction() {
+        let series = [(2.0, 355.0), (4.0, 270.0)];
+        assert_eq!(
+            wind_rose_ascii(&series, "m/s", 11),
+            "Wind rose (mean speed 3.0 m/s)\n     N\n     |\n W --+\n"
+        );
+        assert_eq!(wind_rose_ascii(&series, "m/s", 10), "");
+        assert_eq!(wind_rose_ascii(&[], "m/s", 80), "");
+    }
+}
@@ -0,0 +1,161 @@
+use crate::args::ColorTheme;
+use crate::weather::{PrecipitationType, SymbolCode};
+
+/// ANSI colors of the temperature ranges and weather conditions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub freezing: &'static str,
+    pub cold: &'static str,
+    pub mild: &'static str,
+    pub warm: &'static str,
+    pub hot: &'static str,
+    pub clear: &'static str,
+    pub cloudy: &'static str,
+    pub precipitation: &'static str,
+    pub thunder: &'static str,
+}
+
+/// Bright colors, readable on a dark background
+pub const DARK: Palette = Palette {
+    freezing: "96",
+    cold: "94",
+    mild: "92",
+    warm: "93",
+    hot: "91",
+    clear: "93",
+    cloudy: "37",
+    precipitation: "96",
+    thunder: "95",
+};
+
+/// Dark colors, readable on a light background
+pub const LIGHT: Palette = Palette {
+    freezing: "36",
+    cold: "34",
+    mild: "32",
+    warm: "33",
+    hot: "31",
+    clear: "33",
+    cloudy: "90",
+    precipitation: "34",
+    thunder: "35",
+};
+
+impl ColorTheme {
+    /// Palette of the theme, guessing the background of the terminal for `Auto`
+    pub fn palette(self) -> Palette {
+        match self {
+            ColorTheme::Dark => DARK,
+            ColorTheme::Light => LIGHT,
+            ColorTheme::Auto => match detect_light_background() {
+                true => LIGHT,
+                false => DARK,
+            },
+        }
+    }
+}
+
+/// Whether the terminal has a light background, from `COLORFGBG` (set by rxvt, Konsole and
+/// others as `foreground;background`) or otherwise the terminal application
+fn detect_light_background() -> bool {
+    if let Ok(colors) = std::env::var("COLORFGBG") {
+        // colors 7 and 9 to 15 of the 16 color palette are the light ones
+        if let Some(Ok(background)) = colors.rsplit(';').next().map(str::parse::<u8>) {
+            return background == 7 || background > 8;
+        }
+    }
+    // Terminal.app starts with a light profile
+    std::env::var("TERM_PROGRAM").is_ok_and(|program| program == "Apple_Terminal")
+}
+
+impl Palette {
+    pub fn temperature(&self, celsius: f64) -> &'static str {
+        match celsius {
+            t if t < 0.0 => self.freezing,
+            t if t < 10.0 => self.cold,
+            t if t < 20.0 => self.mild,
+            t if t < 30.0 => self.warm,
+            _ => self.hot,
+        }
+    }
+
+    pub fn condition(&self, symbol_code: SymbolCode) -> &'static str {
+        let condition = symbol_code.base_condition();
+        if condition.contains("thunder") {
+            self.thunder
+        } else if symbol_code.precipitation_type() != PrecipitationType::None {
+            self.precipitation
+        } else if matches!(condition, "clearsky" | "fair") {
+            self.clear
+        } else {
+            self.cloudy
+        }
+    }
+}
+
+/// tmux color number of the temperature: blue when cold, then green, yellow and red
+pub fn tmux_colour(celsius: f64) -> u8 {
+    match celsius {
+        t if t < 5.0 => 33,
+        t if t < 20.0 => 34,
+        t if t <= 30.0 => 220,
+        _ => 196,
+    }
+}
+
+/// Xmobar color of the temperature, in the same ranges as `tmux_colour`
+pub fn xmobar_color(celsius: f64) -> &'static str {
+    match celsius {
+        t if t < 5.0 => "#5fafff",
+        t if t < 20.0 => "#5fd75f",
+        t if t <= 30.0 => "#ffd700",
+        _ => "#ff5f5f",
+    }
+}
+
+/// `text` in the ANSI color `code`
+pub fn paint(text: &str, code: &str) -> String {
+    format!("\x1b[{}m{}\x1b[0m", code, text)
+}
+
+/// Width of `text` in a terminal, leaving out the escape codes added by `paint`
+pub fn visible_width(text: &str) -> usize {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                chars.by_ref().find(|c| *c == 'm');
+            }
+            c => plain.push(c),
+        }
+    }
+    unicode_width::UnicodeWidthStr::width(plain.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visible_width_leaves_out_escape_codes() {
+        assert_eq!(visible_width(&paint("21.0°C", DARK.warm)), 6);
+        assert_eq!(
+            visible_width(&format!(
+                "{} {}",
+                paint("☀️", "93"),
+                paint("Clear Sky", "93")
+            )),
+            12
+        );
+        assert_eq!(visible_width("Cloudy"), 6);
+    }
+
+    #[test]
+    fn temperature_ranges_start_at_their_lower_bound() {
+        assert_eq!(DARK.temperature(-0.1), DARK.freezing);
+        assert_eq!(DARK.temperature(0.0), DARK.cold);
+        assert_eq!(LIGHT.temperature(20.0), LIGHT.warm);
+        assert_eq!(LIGHT.temperature(30.0), LIGHT.hot);
+    }
+}
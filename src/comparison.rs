@@ -0,0 +1,232 @@
+use chrono::{Duration, NaiveDate, NaiveTime, Timelike};
+
+use crate::args::DurationType;
+use crate::color::visible_width;
+use crate::weather::{
+    describe_weather, format_speed, format_temperature, DisplayOptions, SymbolCode, WeatherData,
+    WeatherError,
+};
+
+/// Width of the label column in `display_comparison`
+const LABEL_WIDTH: usize = 15;
+/// Width of the column of the first day in `display_comparison`
+const COLUMN_WIDTH: usize = 36;
+
+/// Overview of one forecast day
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailySummary<'a> {
+    pub date: NaiveDate,
+    /// Symbol code of the six-hour period of the day with the worst weather
+    pub symbol_code: &'a str,
+    pub min_temperature: f64,
+    pub max_temperature: f64,
+    pub average_temperature: f64,
+    /// Total precipitation over the day in mm
+    pub precipitation: f64,
+    pub max_wind_speed: Option<f64>,
+    pub average_wind_speed: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayComparison<'a> {
+    pub day_a_summary: DailySummary<'a>,
+    pub day_b_summary: DailySummary<'a>,
+}
+
+impl WeatherData {
+    /// Summary of the UTC date `date`, or `None` if the forecast has no temperatures for it
+    pub fn daily_summary(&self, date: NaiveDate) -> Option<DailySummary<'_>> {
+        let entries = self.properties.timeseries_for_day(date, None);
+        let from = date.and_time(NaiveTime::MIN).and_utc();
+        let to = from + Duration::days(1);
+        let series = self.get_hourly_temperature_series(from, to);
+        let temperatures = series.iter().map(|(_, temperature)| *temperature);
+        let min_temperature = temperatures.clone().reduce(f64::min)?;
+        let max_temperature = temperatures.reduce(f64::max)?;
+        let average_temperature = self.average_temperature(from, to)?;
+
+        // the six-hour periods cover the whole day both in the hourly and the six-hourly part
+        // of the forecast
+        let periods = entries
+            .iter()
+            .filter(|timeseries| timeseries.time.hour() % 6 == 0);
+        let symbol_code = periods
+            .clone()
+            .filter_map(|timeseries| Some(timeseries.summary(DurationType::Week).ok()?.symbol()))
+            // the earliest period among equally bad ones
+            .rev()
+            .max_by_key(SymbolCode::severity)?
+            .0;
+        let precipitation = periods
+            .filter_map(|timeseries| timeseries.precipitation_amount(DurationType::Week))
+            .sum();
+        let max_wind_speed = entries
+            .iter()
+            .filter_map(|timeseries| timeseries.details().wind_speed)
+            .reduce(f64::max);
+
+        Some(DailySummary {
+            date,
+            symbol_code,
+            min_temperature,
+            max_temperature,
+            average_temperature,
+            precipitation,
+            max_wind_speed,
+            average_wind_speed: self.average_wind_speed(from, to),
+        })
+    }
+
+    /// Summaries of two days side by side, e.g. today and tomorrow
+    pub fn compare_days(&self, day_a: NaiveDate, day_b: NaiveDate) -> Option<DayComparison<'_>> {
+        Some(DayComparison {
+            day_a_summary: self.daily_summary(day_a)?,
+            day_b_summary: self.daily_summary(day_b)?,
+        })
+    }
+
+    /// Today and tomorrow side by side under a header, the `compare` output mode
+    pub(crate) fn display_day_comparison(
+        &self,
+        location_name: &str,
+        options: &DisplayOptions,
+    ) -> Result<String, WeatherError> {
+        let today = options.now().date_naive();
+        let comparison = self
+            .compare_days(today, today + Duration::days(1))
+            .ok_or(WeatherError::MissingData)?;
+        Ok(format!(
+            "Weather for {} today and tomorrow\n{}",
+            location_name,
+            comparison.display_comparison(options)
+        ))
+    }
+}
+
+impl DailySummary<'_> {
+    /// Values shown in a column of `display_comparison`, in the order of `ROW_LABELS`
+    fn fields(&self, options: &DisplayOptions) -> [String; 5] {
+        [
+            self.date.format("%A %d %B").to_string(),
+            describe_weather(self.symbol_code, options),
+            format!(
+                "{} to {} (avg {})",
+                format_temperature(self.min_temperature, options.temp_unit),
+                format_temperature(self.max_temperature, options.temp_unit),
+                format_temperature(self.average_temperature, options.temp_unit),
+            ),
+            format!("{:.1} mm", self.precipitation),
+            match (self.max_wind_speed, self.average_wind_speed) {
+                (Some(max), Some(average)) => format!(
+                    "up to {} (avg {})",
+                    format_speed(max, options.speed_unit),
+                    format_speed(average, options.speed_unit),
+                ),
+                _ => "-".to_string(),
+            },
+        ]
+    }
+}
+
+const ROW_LABELS: [&str; 5] = ["", "Weather", "Temperature", "Precipitation", "Wind"];
+
+impl DayComparison<'_> {
+    /// Both days in two columns, one row per field
+    pub fn display_comparison(&self, options: &DisplayOptions) -> String {
+        let day_a = self.day_a_summary.fields(options);
+        let day_b = self.day_b_summary.fields(options);
+        ROW_LABELS
+            .iter()
+            .zip(day_a.iter().zip(&day_b))
+            .map(|(label, (a, b))| {
+                let padding = COLUMN_WIDTH.saturating_sub(visible_width(a));
+                format!("{:<LABEL_WIDTH$}{}{}{}", label, a, " ".repeat(padding), b)
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::tests::{at, forecast, instant_only, six_hourly};
+
+    #[test]
+    fn summary_covers_the_whole_day() {
+        let weather_data = forecast(vec![
+            (0, six_hourly("cloudy", 0.0, 2.0)),
+            (3, instant_only(8.0)),
+            (6, six_hourly("rainshowers_day", 1.5, 5.0)),
+            (12, six_hourly("rain", 2.0, 4.0)),
+            (18, six_hourly("lightrain", 0.5, 1.0)),
+            (24, six_hourly("heavyrain", 9.0, 20.0)),
+        ]);
+
+        let summary = weather_data.daily_summary(at(0).date_naive()).unwrap();
+        assert_eq!(summary.date, at(0).date_naive());
+        // the earliest of the equally bad periods
+        assert_eq!(summary.symbol_code, "rainshowers_day");
+        assert_eq!(summary.min_temperature, 1.0);
+        assert_eq!(summary.max_temperature, 8.0);
+        assert_eq!(summary.average_temperature, 4.0);
+        assert_eq!(summary.precipitation, 4.0);
+        // no entry has a wind speed
+        assert_eq!(summary.max_wind_speed, None);
+        assert_eq!(summary.average_wind_speed, None);
+    }
+
+    #[test]
+    fn summary_needs_temperatures_and_a_period_summary() {
+        let weather_data = forecast(vec![
+            (0, six_hourly("cloudy", 0.0, 2.0)),
+            (24, instant_only(3.0)),
+            (30, instant_only(4.0)),
+        ]);
+        let day = |offset| at(0).date_naive() + Duration::days(offset);
+
+        assert_eq!(forecast(Vec::new()).daily_summary(day(0)), None);
+        assert_eq!(weather_data.daily_summary(day(1)), None);
+        assert_eq!(weather_data.daily_summary(day(2)), None);
+        assert!(weather_data.compare_days(day(0), day(0)).is_some());
+        assert_eq!(weather_data.compare_days(day(0), day(1)), None);
+        assert_eq!(weather_data.compare_days(day(2), day(0)), None);
+    }
+
+    #[test]
+    fn columns_line_up_after_emoji() {
+        let weather_data = forecast(
+            (0..8)
+                .map(|block| match block < 4 {
+                    true => (6 * block, six_hourly("partlycloudy_day", 0.0, 10.0)),
+                    false => (6 * block, six_hourly("cloudy", 0.0, 5.0)),
+                })
+                .collect(),
+        );
+        let day = at(0).date_naive();
+        let comparison = weather_data
+            .compare_days(day, day + Duration::days(1))
+            .unwrap();
+        let options = DisplayOptions::default();
+
+        let output = comparison.display_comparison(&options);
+        assert!(
+            output.contains("Weather        ⛅ Partly Cloudy (Day)"),
+            "{}",
+            output
+        );
+        let day_b = comparison.day_b_summary.fields(&options);
+        for (line, field) in output.lines().zip(&day_b) {
+            let column = line.strip_suffix(field.as_str()).unwrap();
+            // ⛅ is a single char two columns wide
+            assert_eq!(
+                visible_width(column),
+                LABEL_WIDTH + COLUMN_WIDTH,
+                "{}",
+                line
+            );
+        }
+    }
+}
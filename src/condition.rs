@@ -0,0 +1,518 @@
+use std::fmt::{self, Display};
+
+use crate::weather::SymbolCode;
+
+/// Weather condition of a met.no symbol code, including its time of day
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WeatherCondition {
+    ClearSkyDay,
+    FairDay,
+    PartlyCloudyDay,
+    Cloudy,
+    RainShowersDay,
+    RainShowersAndThunderDay,
+    SleetShowersDay,
+    SnowShowersDay,
+    Rain,
+    HeavyRain,
+    HeavyRainAndThunder,
+    Sleet,
+    Snow,
+    SnowAndThunder,
+    Fog,
+    SleetShowersAndThunderDay,
+    SnowShowersAndThunderDay,
+    RainAndThunder,
+    SleetAndThunder,
+    LightRainShowersAndThunderDay,
+    HeavyRainShowersAndThunderDay,
+    LightSleetShowersAndThunderDay,
+    HeavySleetShowersAndThunderDay,
+    LightSnowShowersAndThunderDay,
+    HeavySnowShowersAndThunderDay,
+    LightRainAndThunder,
+    LightSleetAndThunder,
+    HeavySleetAndThunder,
+    LightSnowAndThunder,
+    HeavySnowAndThunder,
+    LightRainShowersDay,
+    HeavyRainShowersDay,
+    LightSleetShowersDay,
+    HeavySleetShowersDay,
+    LightSnowShowersDay,
+    HeavySnowShowersDay,
+    LightRain,
+    LightSleet,
+    HeavySleet,
+    LightSnow,
+    HeavySnow,
+    ClearSkyNight,
+    FairNight,
+    PartlyCloudyNight,
+    RainShowersNight,
+    RainShowersAndThunderNight,
+    SleetShowersNight,
+    SnowShowersNight,
+    SleetShowersAndThunderNight,
+    SnowShowersAndThunderNight,
+    LightRainShowersAndThunderNight,
+    HeavyRainShowersAndThunderNight,
+    LightSleetShowersAndThunderNight,
+    HeavySleetShowersAndThunderNight,
+    LightSnowShowersAndThunderNight,
+    HeavySnowShowersAndThunderNight,
+    LightRainShowersNight,
+    HeavyRainShowersNight,
+    LightSleetShowersNight,
+    HeavySleetShowersNight,
+    LightSnowShowersNight,
+    HeavySnowShowersNight,
+    ClearSkyPolarTwilight,
+    FairPolarTwilight,
+    PartlyCloudyPolarTwilight,
+    RainShowersPolarTwilight,
+    RainShowersAndThunderPolarTwilight,
+    SleetShowersPolarTwilight,
+    SnowShowersPolarTwilight,
+    SleetShowersAndThunderPolarTwilight,
+    SnowShowersAndThunderPolarTwilight,
+    LightRainShowersAndThunderPolarTwilight,
+    HeavyRainShowersAndThunderPolarTwilight,
+    LightSleetShowersAndThunderPolarTwilight,
+    HeavySleetShowersAndThunderPolarTwilight,
+    LightSnowShowersAndThunderPolarTwilight,
+    HeavySnowShowersAndThunderPolarTwilight,
+    LightRainShowersPolarTwilight,
+    HeavyRainShowersPolarTwilight,
+    LightSleetShowersPolarTwilight,
+    HeavySleetShowersPolarTwilight,
+    LightSnowShowersPolarTwilight,
+    HeavySnowShowersPolarTwilight,
+}
+
+/// Time of day of a condition, from the suffix of its symbol code
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TimeVariant {
+    Day,
+    Night,
+    PolarTwilight,
+    /// Conditions such as rain or fog that look the same at any time, without a suffix
+    AllDay,
+}
+
+/// Symbol code and description of every condition
+const CONDITIONS: &[(WeatherCondition, &str, &str)] = &[
+    (WeatherCondition::ClearSkyDay, "clearsky_day", "☀️ Clear Sky (Day)"),
+    (WeatherCondition::FairDay, "fair_day", "🌤️ Fair (Day)"),
+    (WeatherCondition::PartlyCloudyDay, "partlycloudy_day", "⛅ Partly Cloudy (Day)"),
+    (WeatherCondition::Cloudy, "cloudy", "☁️ Cloudy"),
+    (WeatherCondition::RainShowersDay, "rainshowers_day", "🌦️ Rain Showers (Day)"),
+    (
+        WeatherCondition::RainShowersAndThunderDay,
+        "rainshowersandthunder_day",
+        "⛈️ Rain Showers and Thunder (Day)",
+    ),
+    (WeatherCondition::SleetShowersDay, "sleetshowers_day", "🌨️ Sleet Showers (Day)"),
+    (WeatherCondition::SnowShowersDay, "snowshowers_day", "❄️ Snow Showers (Day)"),
+    (WeatherCondition::Rain, "rain", "🌧️ Rain"),
+    (WeatherCondition::HeavyRain, "heavyrain", "🌧️ Heavy Rain"),
+    (WeatherCondition::HeavyRainAndThunder, "heavyrainandthunder", "⛈️ Heavy Rain and Thunder"),
+    (WeatherCondition::Sleet, "sleet", "🌨️ Sleet"),
+    (WeatherCondition::Snow, "snow", "❄️ Snow"),
+    (WeatherCondition::SnowAndThunder, "snowandthunder", "⛈️ Snow and Thunder"),
+    (WeatherCondition::Fog, "fog", "🌫️ Fog"),
+    (
+        WeatherCondition::SleetShowersAndThunderDay,
+        "sleetshowersandthunder_day",
+        "⛈️ Sleet Showers and Thunder (Day)",
+    ),
+    (
+        WeatherCondition::SnowShowersAndThunderDay,
+        "snowshowersandthunder_day",
+        "⛈️ Snow Showers and Thunder (Day)",
+    ),
+    (WeatherCondition::RainAndThunder, "rainandthunder", "⛈️ Rain and Thunder"),
+    (WeatherCondition::SleetAndThunder, "sleetandthunder", "⛈️ Sleet and Thunder"),
+    (
+        WeatherCondition::LightRainShowersAndThunderDay,
+        "lightrainshowersandthunder_day",
+        "⛈️ Light Rain Showers and Thunder (Day)",
+    ),
+    (
+        WeatherCondition::HeavyRainShowersAndThunderDay,
+        "heavyrainshowersandthunder_day",
+        "⛈️ Heavy Rain Showers and Thunder (Day)",
+    ),
+    (
+        WeatherCondition::LightSleetShowersAndThunderDay,
+        "lightssleetshowersandthunder_day",
+        "⛈️ Light Sleet Showers and Thunder (Day)",
+    ),
+    (
+        WeatherCondition::HeavySleetShowersAndThunderDay,
+        "heavysleetshowersandthunder_day",
+        "⛈️ Heavy Sleet Showers and Thunder (Day)",
+    ),
+    (
+        WeatherCondition::LightSnowShowersAndThunderDay,
+        "lightssnowshowersandthunder_day",
+        "⛈️ Light Snow Showers and Thunder (Day)",
+    ),
+    (
+        WeatherCondition::HeavySnowShowersAndThunderDay,
+        "heavysnowshowersandthunder_day",
+        "⛈️ Heavy Snow Showers and Thunder (Day)",
+    ),
+    (WeatherCondition::LightRainAndThunder, "lightrainandthunder", "⛈️ Light Rain and Thunder"),
+    (WeatherCondition::LightSleetAndThunder, "lightsleetandthunder", "⛈️ Light Sleet and Thunder"),
+    (WeatherCondition::HeavySleetAndThunder, "heavysleetandthunder", "⛈️ Heavy Sleet and Thunder"),
+    (WeatherCondition::LightSnowAndThunder, "lightsnowandthunder", "⛈️ Light Snow and Thunder"),
+    (WeatherCondition::HeavySnowAndThunder, "heavysnowandthunder", "⛈️ Heavy Snow and Thunder"),
+    (WeatherCondition::LightRainShowersDay, "lightrainshowers_day", "🌦️ Light Rain Showers (Day)"),
+    (WeatherCondition::HeavyRainShowersDay, "heavyrainshowers_day", "🌦️ Heavy Rain Showers (Day)"),
+    (
+        WeatherCondition::LightSleetShowersDay,
+        "lightsleetshowers_day",
+        "🌦️ Light Sleet Showers (Day)",
+    ),
+    (
+        WeatherCondition::HeavySleetShowersDay,
+        "heavysleetshowers_day",
+        "🌦️ Heavy Sleet Showers (Day)",
+    ),
+    (WeatherCondition::LightSnowShowersDay, "lightsnowshowers_day", "🌦️ Light Snow Showers (Day)"),
+    (WeatherCondition::HeavySnowShowersDay, "heavysnowshowers_day", "🌦️ Heavy Snow Showers (Day)"),
+    (WeatherCondition::LightRain, "lightrain", "🌧️ Light Rain"),
+    (WeatherCondition::LightSleet, "lightsleet", "🌨️ Light Sleet"),
+    (WeatherCondition::HeavySleet, "heavysleet", "🌨️ Heavy Sleet"),
+    (WeatherCondition::LightSnow, "lightsnow", "❄️ Light Snow"),
+    (WeatherCondition::HeavySnow, "heavysnow", "❄️ Heavy Snow"),
+    (WeatherCondition::ClearSkyNight, "clearsky_night", "🌙 Clear Sky (Night)"),
+    (WeatherCondition::FairNight, "fair_night", "🌙 Fair (Night)"),
+    (WeatherCondition::PartlyCloudyNight, "partlycloudy_night", "🌙 Partly Cloudy (Night)"),
+    (WeatherCondition::RainShowersNight, "rainshowers_night", "🌦️ Rain Showers (Night)"),
+    (
+        WeatherCondition::RainShowersAndThunderNight,
+        "rainshowersandthunder_night",
+        "⛈️ Rain Showers and Thunder (Night)",
+    ),
+    (WeatherCondition::SleetShowersNight, "sleetshowers_night", "🌨️ Sleet Showers (Night)"),
+    (WeatherCondition::SnowShowersNight, "snowshowers_night", "❄️ Snow Showers (Night)"),
+    (
+        WeatherCondition::SleetShowersAndThunderNight,
+        "sleetshowersandthunder_night",
+        "⛈️ Sleet Showers and Thunder (Night)",
+    ),
+    (
+        WeatherCondition::SnowShowersAndThunderNight,
+        "snowshowersandthunder_night",
+        "⛈️ Snow Showers and Thunder (Night)",
+    ),
+    (
+        WeatherCondition::LightRainShowersAndThunderNight,
+        "lightrainshowersandthunder_night",
+        "⛈️ Light Rain Showers and Thunder (Night)",
+    ),
+    (
+        WeatherCondition::HeavyRainShowersAndThunderNight,
+        "heavyrainshowersandthunder_night",
+        "⛈️ Heavy Rain Showers and Thunder (Night)",
+    ),
+    (
+        WeatherCondition::LightSleetShowersAndThunderNight,
+        "lightssleetshowersandthunder_night",
+        "⛈️ Light Sleet Showers and Thunder (Night)",
+    ),
+    (
+        WeatherCondition::HeavySleetShowersAndThunderNight,
+        "heavysleetshowersandthunder_night",
+        "⛈️ Heavy Sleet Showers and Thunder (Night)",
+    ),
+    (
+        WeatherCondition::LightSnowShowersAndThunderNight,
+        "lightssnowshowersandthunder_night",
+        "⛈️ Light Snow Showers and Thunder (Night)",
+    ),
+    (
+        WeatherCondition::HeavySnowShowersAndThunderNight,
+        "heavysnowshowersandthunder_night",
+        "⛈️ Heavy Snow Showers and Thunder (Night)",
+    ),
+    (
+        WeatherCondition::LightRainShowersNight,
+        "lightrainshowers_night",
+        "🌦️ Light Rain Showers (Night)",
+    ),
+    (
+        WeatherCondition::HeavyRainShowersNight,
+        "heavyrainshowers_night",
+        "🌦️ Heavy Rain Showers (Night)",
+    ),
+    (
+        WeatherCondition::LightSleetShowersNight,
+        "lightsleetshowers_night",
+        "🌦️ Light Sleet Showers (Night)",
+    ),
+    (
+        WeatherCondition::HeavySleetShowersNight,
+        "heavysleetshowers_night",
+        "🌦️ Heavy Sleet Showers (Night)",
+    ),
+    (
+        WeatherCondition::LightSnowShowersNight,
+        "lightsnowshowers_night",
+        "🌦️ Light Snow Showers (Night)",
+    ),
+    (
+        WeatherCondition::HeavySnowShowersNight,
+        "heavysnowshowers_night",
+        "🌦️ Heavy Snow Showers (Night)",
+    ),
+    (
+        WeatherCondition::ClearSkyPolarTwilight,
+        "clearsky_polartwilight",
+        "🌌 Clear Sky (Polar Twilight)",
+    ),
+    (WeatherCondition::FairPolarTwilight, "fair_polartwilight", "🌌 Fair (Polar Twilight)"),
+    (
+        WeatherCondition::PartlyCloudyPolarTwilight,
+        "partlycloudy_polartwilight",
+        "🌌 Partly Cloudy (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::RainShowersPolarTwilight,
+        "rainshowers_polartwilight",
+        "🌌 Rain Showers (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::RainShowersAndThunderPolarTwilight,
+        "rainshowersandthunder_polartwilight",
+        "🌌 Rain Showers and Thunder (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::SleetShowersPolarTwilight,
+        "sleetshowers_polartwilight",
+        "🌌 Sleet Showers (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::SnowShowersPolarTwilight,
+        "snowshowers_polartwilight",
+        "🌌 Snow Showers (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::SleetShowersAndThunderPolarTwilight,
+        "sleetshowersandthunder_polartwilight",
+        "🌌 Sleet Showers and Thunder (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::SnowShowersAndThunderPolarTwilight,
+        "snowshowersandthunder_polartwilight",
+        "🌌 Snow Showers and Thunder (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::LightRainShowersAndThunderPolarTwilight,
+        "lightrainshowersandthunder_polartwilight",
+        "🌌 Light Rain Showers and Thunder (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::HeavyRainShowersAndThunderPolarTwilight,
+        "heavyrainshowersandthunder_polartwilight",
+        "🌌 Heavy Rain Showers and Thunder (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::LightSleetShowersAndThunderPolarTwilight,
+        "lightssleetshowersandthunder_polartwilight",
+        "🌌 Light Sleet Showers and Thunder (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::HeavySleetShowersAndThunderPolarTwilight,
+        "heavysleetshowersandthunder_polartwilight",
+        "🌌 Heavy Sleet Showers and Thunder (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::LightSnowShowersAndThunderPolarTwilight,
+        "lightssnowshowersandthunder_polartwilight",
+        "🌌 Light Snow Showers and Thunder (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::HeavySnowShowersAndThunderPolarTwilight,
+        "heavysnowshowersandthunder_polartwilight",
+        "🌌 Heavy Snow Showers and Thunder (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::LightRainShowersPolarTwilight,
+        "lightrainshowers_polartwilight",
+        "🌌 Light Rain Showers (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::HeavyRainShowersPolarTwilight,
+        "heavyrainshowers_polartwilight",
+        "🌌 Heavy Rain Showers (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::LightSleetShowersPolarTwilight,
+        "lightsleetshowers_polartwilight",
+        "🌌 Light Sleet Showers (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::HeavySleetShowersPolarTwilight,
+        "heavysleetshowers_polartwilight",
+        "🌌 Heavy Sleet Showers (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::LightSnowShowersPolarTwilight,
+        "lightsnowshowers_polartwilight",
+        "🌌 Light Snow Showers (Polar Twilight)",
+    ),
+    (
+        WeatherCondition::HeavySnowShowersPolarTwilight,
+        "heavysnowshowers_polartwilight",
+        "🌌 Heavy Snow Showers (Polar Twilight)",
+    ),
+];
+
+impl WeatherCondition {
+    /// The met.no symbol code of the condition, e.g. `partlycloudy_night`
+    pub fn symbol_code(&self) -> &'static str {
+        self.entry().1
+    }
+
+    /// Whether the condition has thunder or heavy precipitation, for alerts
+    pub fn is_severe(&self) -> bool {
+        let code = self.symbol_code();
+        code.contains("thunder") || code.starts_with("heavy")
+    }
+
+    pub fn time_of_day(&self) -> TimeVariant {
+        match self.symbol_code().rsplit_once('_') {
+            Some((_, "day")) => TimeVariant::Day,
+            Some((_, "night")) => TimeVariant::Night,
+            Some((_, "polartwilight")) => TimeVariant::PolarTwilight,
+            _ => TimeVariant::AllDay,
+        }
+    }
+
+    fn entry(&self) -> &'static (WeatherCondition, &'static str, &'static str) {
+        CONDITIONS
+            .iter()
+            .find(|(condition, _, _)| condition == self)
+            .expect("every condition is listed in CONDITIONS")
+    }
+}
+
+/// Emoji and description, e.g. `⛅ Partly Cloudy (Day)`
+impl Display for WeatherCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.entry().2)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownSymbolCode(pub String);
+
+impl Display for UnknownSymbolCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown symbol code \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for UnknownSymbolCode {}
+
+impl TryFrom<&str> for WeatherCondition {
+    type Error = UnknownSymbolCode;
+
+    fn try_from(symbol_code: &str) -> Result<Self, Self::Error> {
+        CONDITIONS
+            .iter()
+            .find(|(_, code, _)| *code == symbol_code)
+            .map(|(condition, _, _)| *condition)
+            .ok_or_else(|| UnknownSymbolCode(symbol_code.to_string()))
+    }
+}
+
+impl TryFrom<SymbolCode<'_>> for WeatherCondition {
+    type Error = UnknownSymbolCode;
+
+    fn try_from(symbol_code: SymbolCode<'_>) -> Result<Self, Self::Error> {
+        WeatherCondition::try_from(symbol_code.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_listed_symbol_code_parses_back_to_its_condition() {
+        for (condition, code, _) in CONDITIONS {
+            assert_eq!(WeatherCondition::try_from(*code), Ok(*condition));
+            assert_eq!(condition.symbol_code(), *code);
+        }
+    }
+
+    #[test]
+    fn unknown_symbol_codes_fail_to_parse() {
+        for code in ["", "sunny", "Cloudy", "partlycloudy", "rain_day"] {
+            assert_eq!(
+                WeatherCondition::try_from(code),
+                Err(UnknownSymbolCode(code.to_string()))
+            );
+        }
+        assert_eq!(
+            UnknownSymbolCode("sunny".to_string()).to_string(),
+            "Unknown symbol code \"sunny\""
+        );
+    }
+
+    #[test]
+    fn conditions_display_their_emoji_and_description() {
+        assert_eq!(WeatherCondition::Cloudy.to_string(), "☁️ Cloudy");
+        // without the stray "P" the old description had
+        assert_eq!(
+            WeatherCondition::PartlyCloudyNight.to_string(),
+            "🌙 Partly Cloudy (Night)"
+        );
+        assert_eq!(
+            WeatherCondition::HeavyRainAndThunder.to_string(),
+            "⛈️ Heavy Rain and Thunder"
+        );
+    }
+
+    #[test]
+    fn thunder_and_heavy_precipitation_are_severe() {
+        for severe in [
+            WeatherCondition::RainAndThunder,
+            WeatherCondition::LightSnowShowersAndThunderPolarTwilight,
+            WeatherCondition::HeavyRain,
+            WeatherCondition::HeavySleetShowersNight,
+        ] {
+            assert!(severe.is_severe(), "{:?} is severe", severe);
+        }
+        for mild in [
+            WeatherCondition::LightRain,
+            WeatherCondition::Rain,
+            WeatherCondition::Cloudy,
+            WeatherCondition::ClearSkyDay,
+        ] {
+            assert!(!mild.is_severe(), "{:?} is not severe", mild);
+        }
+    }
+
+    #[test]
+    fn time_of_day_follows_the_symbol_code_suffix() {
+        assert_eq!(WeatherCondition::FairDay.time_of_day(), TimeVariant::Day);
+        assert_eq!(
+            WeatherCondition::RainShowersAndThunderNight.time_of_day(),
+            TimeVariant::Night
+        );
+        assert_eq!(
+            WeatherCondition::ClearSkyPolarTwilight.time_of_day(),
+            TimeVariant::PolarTwilight
+        );
+        assert_eq!(WeatherCondition::Fog.time_of_day(), TimeVariant::AllDay);
+        assert_eq!(
+            WeatherCondition::HeavySnowAndThunder.time_of_day(),
+            TimeVariant::AllDay
+        );
+    }
+}
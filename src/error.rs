@@ -0,0 +1,100 @@
+use std::fmt::{self, Display};
+
+use crate::args::WeatherArgs;
+use crate::geocoding::CoordinatesError;
+use crate::history::HistoryError;
+use crate::locations::LocationsError;
+use crate::weather::WeatherError;
+
+#[derive(Debug)]
+pub enum AppError {
+    Network(String),
+    LocationNotFound(String),
+    Coordinates(CoordinatesError),
+    Locations(LocationsError),
+    History(HistoryError),
+    Weather(WeatherError),
+    Io(std::io::Error),
+    /// A condition watched by an `--alert-*` flag is forecast
+    Alert(String),
+}
+
+impl Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Network(e) => write!(f, "Network error: {}", e),
+            AppError::LocationNotFound(query) => write!(f, "No location found for \"{}\"", query),
+            AppError::Coordinates(e) => write!(f, "Invalid coordinates: {}", e),
+            AppError::Locations(e) => write!(f, "{}", e),
+            AppError::History(e) => write!(f, "{}", e),
+            AppError::Weather(e) => write!(f, "{}", e),
+            AppError::Io(e) => write!(f, "{}", e),
+            AppError::Alert(message) => write!(f, "Alert: {}", message),
+        }
+    }
+}
+
+impl AppError {
+    /// Exit code of the process failing with this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::Alert(_) => 2,
+            _ => 1,
+        }
+    }
+
+    /// Network error that mentions the configured `--timeout` when the request timed out
+    pub fn network(e: reqwest::Error, args: &WeatherArgs) -> AppError {
+        match e.is_timeout() {
+            true => AppError::Network(format!(
+                "request timed out after {} seconds (see --timeout)",
+                args.timeout.unwrap()
+            )),
+            false => AppError::Network(e.to_string()),
+        }
+    }
+
+    /// Weather error whose network failures are reported like `AppError::network`
+    pub fn weather(e: WeatherError, args: &WeatherArgs) -> AppError {
+        match e {
+            WeatherError::Network(e) => AppError::network(e, args),
+            e => AppError::Weather(e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(e: reqwest::Error) -> Self {
+        AppError::Network(e.to_string())
+    }
+}
+
+impl From<CoordinatesError> for AppError {
+    fn from(e: CoordinatesError) -> Self {
+        AppError::Coordinates(e)
+    }
+}
+
+impl From<LocationsError> for AppError {
+    fn from(e: LocationsError) -> Self {
+        AppError::Locations(e)
+    }
+}
+
+impl From<HistoryError> for AppError {
+    fn from(e: HistoryError) -> Self {
+        AppError::History(e)
+    }
+}
+
+impl From<WeatherError> for AppError {
+    fn from(e: WeatherError) -> Self {
+        AppError::Weather(e)
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Io(e)
+    }
+}
@@ -0,0 +1,62 @@
+use ::arrow::array::{ArrayRef, Float64Array, StringArray, TimestampSecondArray};
+use ::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use ::arrow::error::ArrowError;
+use ::arrow::record_batch::RecordBatch;
+use chrono::Utc;
+use std::sync::Arc;
+
+use crate::args::DurationType;
+use crate::weather::{Timeseries, WeatherData};
+
+impl WeatherData {
+    /// Columnar view of the timeseries covered by `duration`, for Arrow-native tools
+    pub fn to_arrow_record_batch(&self, duration: DurationType) -> Result<RecordBatch, ArrowError> {
+        let timeseries: Vec<&Timeseries> = self
+            .lazy_timeseries(duration, Utc::now(), None)
+            .collect();
+
+        let schema = Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Second, Some("UTC".into())),
+                false,
+            ),
+            Field::new("temperature", DataType::Float64, true),
+            Field::new("wind_speed", DataType::Float64, true),
+            Field::new("precipitation", DataType::Float64, true),
+            Field::new("symbol_code", DataType::Utf8, true),
+        ]);
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                TimestampSecondArray::from_iter_values(
+                    timeseries.iter().map(|timeseries| timeseries.time.timestamp()),
+                )
+                .with_timezone("UTC"),
+            ),
+            Arc::new(Float64Array::from_iter(
+                timeseries
+                    .iter()
+                    .map(|timeseries| timeseries.details().air_temperature),
+            )),
+            Arc::new(Float64Array::from_iter(
+                timeseries
+                    .iter()
+                    .map(|timeseries| timeseries.details().wind_speed),
+            )),
+            Arc::new(Float64Array::from_iter(
+                timeseries
+                    .iter()
+                    .map(|timeseries| timeseries.precipitation_amount(duration)),
+            )),
+            Arc::new(StringArray::from_iter(timeseries.iter().map(|timeseries| {
+                timeseries
+                    .summary(duration)
+                    .ok()
+                    .map(|summary| summary.symbol_code.as_str())
+            }))),
+        ];
+
+        RecordBatch::try_new(Arc::new(schema), columns)
+    }
+}
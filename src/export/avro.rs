@@ -0,0 +1,49 @@
+use apache_avro::types::{Record, Value};
+use apache_avro::{Schema, Writer};
+use chrono::Utc;
+
+use crate::args::DurationType;
+use crate::weather::WeatherData;
+
+const WEATHER_RECORD_SCHEMA: &str = r#"
+{
+    "type": "record",
+    "name": "WeatherRecord",
+    "namespace": "weather_cli",
+    "fields": [
+        { "name": "time", "type": { "type": "long", "logicalType": "timestamp-millis" } },
+        { "name": "temperature", "type": ["null", "double"], "default": null },
+        { "name": "wind_speed", "type": ["null", "double"], "default": null },
+        { "name": "precipitation", "type": ["null", "double"], "default": null },
+        { "name": "symbol_code", "type": ["null", "string"], "default": null }
+    ]
+}
+"#;
+
+impl WeatherData {
+    /// Avro object container holding one `WeatherRecord` per timeseries entry covered by `duration`
+    // apache_avro::Error is large, but callers expect the crate's own error type
+    #[allow(clippy::result_large_err)]
+    pub fn to_avro_record(&self, duration: DurationType) -> Result<Vec<u8>, apache_avro::Error> {
+        let schema = Schema::parse_str(WEATHER_RECORD_SCHEMA)?;
+        let mut writer = Writer::new(&schema, Vec::new());
+
+        for timeseries in self.lazy_timeseries(duration, Utc::now(), None) {
+            let mut record = Record::new(writer.schema()).expect("schema is a record");
+            record.put("time", Value::TimestampMillis(timeseries.time.timestamp_millis()));
+            record.put("temperature", timeseries.details().air_temperature);
+            record.put("wind_speed", timeseries.details().wind_speed);
+            record.put("precipitation", timeseries.precipitation_amount(duration));
+            record.put(
+                "symbol_code",
+                timeseries
+                    .summary(duration)
+                    .ok()
+                    .map(|summary| summary.symbol_code.clone()),
+            );
+            writer.append(record)?;
+        }
+
+        writer.into_inner()
+    }
+}
@@ -0,0 +1,215 @@
+use ::capnp::message::Builder;
+
+use crate::weather::{Data, Details, Summary, Timeseries, Units, WeatherData};
+
+use self::weather_capnp::{timeseries, units, weather_data};
+
+::capnp::generated_code!(pub mod weather_capnp);
+
+impl WeatherData {
+    /// Cap'n Proto message laid out as `weather.capnp`, with the forecast stored column by column
+    pub fn to_capnp(&self) -> Result<Vec<u8>, ::capnp::Error> {
+        let mut message = Builder::new_default();
+        let mut root = message.init_root::<weather_data::Builder>();
+
+        root.set_type(self.r#type.as_str());
+        root.set_geometry_type(self.geometry.r#type.as_str());
+        root.set_coordinates(self.geometry.coordinates.as_slice())?;
+        root.set_updated_at(self.properties.meta.updated_at.timestamp());
+        write_units(root.reborrow().init_units(), &self.properties.meta.units);
+        write_timeseries(root.init_timeseries(), &self.properties.timeseries)?;
+
+        let mut bytes = Vec::new();
+        ::capnp::serialize::write_message(&mut bytes, &message)?;
+        Ok(bytes)
+    }
+}
+
+fn write_units(mut builder: units::Builder, units: &Units) {
+    if let Some(unit) = &units.air_pressure_at_sea_level {
+        builder.set_air_pressure_at_sea_level(unit.as_str());
+    }
+    if let Some(unit) = &units.air_temperature {
+        builder.set_air_temperature(unit.as_str());
+    }
+    if let Some(unit) = &units.cloud_area_fraction {
+        builder.set_cloud_area_fraction(unit.as_str());
+    }
+    if let Some(unit) = &units.precipitation_amount {
+        builder.set_precipitation_amount(unit.as_str());
+    }
+    if let Some(unit) = &units.relative_humidity {
+        builder.set_relative_humidity(unit.as_str());
+    }
+    if let Some(unit) = &units.wind_from_direction {
+        builder.set_wind_from_direction(unit.as_str());
+    }
+    if let Some(unit) = &units.wind_speed {
+        builder.set_wind_speed(unit.as_str());
+    }
+}
+
+/// Instant values picked by `value`, NaN where missing
+fn instant_column(timeseries: &[Timeseries], value: fn(&Details) -> Option<f64>) -> Vec<f64> {
+    timeseries
+        .iter()
+        .map(|timeseries| value(&timeseries.data.instant.details).unwrap_or(f64::NAN))
+        .collect()
+}
+
+/// Summary and details of one of the `next_*` forecasts of an entry
+type PeriodForecast<'a> = (&'a Summary, &'a Option<Details>);
+
+/// Symbol codes and precipitation of the period forecast picked by `forecast`, empty and NaN
+/// where there is none
+fn forecast_columns(
+    timeseries: &[Timeseries],
+    forecast: fn(&Data) -> Option<PeriodForecast>,
+) -> (Vec<&str>, Vec<f64>) {
+    timeseries
+        .iter()
+        .map(|timeseries| match forecast(&timeseries.data) {
+            Some((summary, details)) => (
+                summary.symbol_code.as_str(),
+                details
+                    .as_ref()
+                    .and_then(|details| details.precipitation_amount)
+                    .unwrap_or(f64::NAN),
+            ),
+            None => ("", f64::NAN),
+        })
+        .unzip()
+}
+
+fn write_timeseries(
+    mut builder: timeseries::Builder,
+    timeseries: &[Timeseries],
+) -> ::capnp::Result<()> {
+    let column = |value| instant_column(timeseries, value);
+    let times: Vec<i64> = timeseries
+        .iter()
+        .map(|timeseries| timeseries.time.timestamp())
+        .collect();
+    builder.set_time(times.as_slice())?;
+    builder.set_air_pressure_at_sea_level(
+        column(|details| details.air_pressure_at_sea_level).as_slice(),
+    )?;
+    builder.set_air_temperature(column(|details| details.air_temperature).as_slice())?;
+    builder.set_cloud_area_fraction(column(|details| details.cloud_area_fraction).as_slice())?;
+    builder.set_precipitation_amount(column(|details| details.precipitation_amount).as_slice())?;
+    builder.set_relative_humidity(column(|details| details.relative_humidity).as_slice())?;
+    builder.set_wind_from_direction(column(|details| details.wind_from_direction).as_slice())?;
+    builder.set_wind_speed(column(|details| details.wind_speed).as_slice())?;
+
+    let (symbol_codes, precipitation) = forecast_columns(timeseries, |data| {
+        let next = data.next_1_hours.as_ref()?;
+        Some((&next.summary, &next.details))
+    });
+    builder.set_next1_hours_symbol_code(symbol_codes.as_slice())?;
+    builder.set_next1_hours_precipitation_amount(precipitation.as_slice())?;
+    let (symbol_codes, precipitation) = forecast_columns(timeseries, |data| {
+        let next = data.next_6_hours.as_ref()?;
+        Some((&next.summary, &next.details))
+    });
+    builder.set_next6_hours_symbol_code(symbol_codes.as_slice())?;
+    builder.set_next6_hours_precipitation_amount(precipitation.as_slice())?;
+    let (symbol_codes, _) = forecast_columns(timeseries, |data| {
+        let next = data.next_12_hours.as_ref()?;
+        Some((&next.summary, &next.details))
+    });
+    builder.set_next12_hours_symbol_code(symbol_codes.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use ::capnp::message::ReaderOptions;
+
+    use super::weather_capnp::weather_data;
+    use crate::weather::WeatherData;
+
+    const FORECAST: &str = r#"{
+        "type": "Feature",
+        "geometry": { "type": "Point", "coordinates": [10.75, 59.91, 0] },
+        "properties": {
+            "meta": {
+                "updated_at": "2026-10-16T17:37:00Z",
+                "units": { "air_temperature": "celsius", "wind_speed": "m/s" }
+            },
+            "timeseries": [
+                {
+                    "time": "2026-10-16T18:00:00Z",
+                    "data": {
+                        "instant": { "details": { "air_temperature": 24.7, "wind_speed": 6.7 } },
+                        "next_1_hours": {
+                            "summary": { "symbol_code": "heavyrainandthunder" },
+                            "details": { "precipitation_amount": 2.4 }
+                        },
+                        "next_12_hours": { "summary": { "symbol_code": "cloudy" } }
+                    }
+                },
+                {
+                    "time": "2026-10-16T19:00:00Z",
+                    "data": { "instant": { "details": { "air_temperature": -3.5 } } }
+                }
+            ]
+        }
+    }"#;
+
+    /// Numbers of a list, with the NaN of missing values as `None`
+    fn numbers(list: ::capnp::primitive_list::Reader<f64>) -> Vec<Option<f64>> {
+        list.iter().map(|value| (!value.is_nan()).then_some(value)).collect()
+    }
+
+    fn texts(list: ::capnp::text_list::Reader) -> Vec<String> {
+        list.iter()
+            .map(|text| text.unwrap().to_string().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn to_capnp_reads_back_with_the_generated_reader() {
+        let data: WeatherData = serde_json::from_str(FORECAST).unwrap();
+        let bytes = data.to_capnp().unwrap();
+
+        let message = ::capnp::serialize::read_message(bytes.as_slice(), ReaderOptions::new())
+            .unwrap();
+        let root = message.get_root::<weather_data::Reader>().unwrap();
+
+        assert_eq!(root.get_type().unwrap(), "Feature");
+        assert_eq!(root.get_geometry_type().unwrap(), "Point");
+        let coordinates: Vec<f64> = root.get_coordinates().unwrap().iter().collect();
+        assert_eq!(coordinates, [10.75, 59.91, 0.0]);
+        assert_eq!(root.get_updated_at(), data.properties.meta.updated_at.timestamp());
+
+        let units = root.get_units().unwrap();
+        assert_eq!(units.get_air_temperature().unwrap(), "celsius");
+        assert_eq!(units.get_wind_speed().unwrap(), "m/s");
+        assert!(!units.has_air_pressure_at_sea_level());
+
+        let timeseries = root.get_timeseries().unwrap();
+        let times: Vec<i64> = timeseries.get_time().unwrap().iter().collect();
+        let expected: Vec<i64> = data
+            .properties
+            .timeseries
+            .iter()
+            .map(|timeseries| timeseries.time.timestamp())
+            .collect();
+        assert_eq!(times, expected);
+        assert_eq!(
+            numbers(timeseries.get_air_temperature().unwrap()),
+            [Some(24.7), Some(-3.5)]
+        );
+        assert_eq!(numbers(timeseries.get_wind_speed().unwrap()), [Some(6.7), None]);
+        assert_eq!(numbers(timeseries.get_relative_humidity().unwrap()), [None, None]);
+        assert_eq!(
+            texts(timeseries.get_next1_hours_symbol_code().unwrap()),
+            ["heavyrainandthunder", ""]
+        );
+        assert_eq!(
+            numbers(timeseries.get_next1_hours_precipitation_amount().unwrap()),
+            [Some(2.4), None]
+        );
+        assert_eq!(texts(timeseries.get_next6_hours_symbol_code().unwrap()), ["", ""]);
+        assert_eq!(texts(timeseries.get_next12_hours_symbol_code().unwrap()), ["cloudy", ""]);
+    }
+}
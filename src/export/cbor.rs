@@ -0,0 +1,14 @@
+use crate::weather::WeatherData;
+
+impl WeatherData {
+    /// CBOR (RFC 7049) encoding, as used by MQTT and CoAP based IoT tooling
+    pub fn to_cbor(&self) -> Result<Vec<u8>, ciborium::ser::Error<std::io::Error>> {
+        let mut buffer = Vec::new();
+        ciborium::into_writer(self, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<WeatherData, ciborium::de::Error<std::io::Error>> {
+        ciborium::from_reader(bytes)
+    }
+}
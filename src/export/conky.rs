@@ -0,0 +1,92 @@
+use crate::args::DurationType;
+use crate::weather::{
+    describe_weather, entry_time, format_temperature, DisplayOptions, WeatherData,
+};
+
+/// Hourly entries listed below the current conditions
+const CONKY_HOURS: usize = 4;
+
+impl WeatherData {
+    /// Conky text for `execpi`: the condition emoji in a large font, the temperature and a table
+    /// of the next hours
+    pub fn to_conky_template(&self, location: &str, options: &DisplayOptions) -> String {
+        let Some(current) = self
+            .lazy_timeseries(DurationType::Now, options.now(), options.timezone)
+            .next()
+        else {
+            return String::new();
+        };
+        // `$` starts a Conky variable
+        let location = location.replace('$', "$$");
+
+        let mut output = String::new();
+        if let Ok(summary) = current.summary(DurationType::Now) {
+            let description = describe_weather(&summary.symbol_code, options);
+            let (emoji, text) = match description.split_once(' ') {
+                Some(parts) if !options.no_emoji => parts,
+                _ => ("", description.as_str()),
+            };
+            output.push_str(&format!("${{font :size=28}}{}${{font}}", emoji));
+            if let Some(temperature) = current.details().air_temperature {
+                output.push_str(&format!(
+                    " ${{font :size=16}}{}${{font}}",
+                    format_temperature(temperature, options.temp_unit)
+                ));
+            }
+            output.push_str(&format!(
+                "\n${{color grey}}{} — {}${{color}}\n",
+                text, location
+            ));
+        }
+
+        output.push_str("${color grey}Time${goto 60}Weather${alignr}Temperature${color}\n");
+        for timeseries in self
+            .properties
+            .timeseries
+            .iter()
+            .filter(|timeseries| timeseries.time > current.time)
+            .take(CONKY_HOURS)
+        {
+            let Ok(summary) = timeseries.summary(DurationType::Today) else {
+                continue;
+            };
+            output.push_str(&format!(
+                "{}${{goto 60}}{}${{alignr}}{}\n",
+                entry_time(timeseries, options).format("%H:%M"),
+                describe_weather(&summary.symbol_code, options),
+                timeseries
+                    .details()
+                    .air_temperature
+                    .map(|temperature| format_temperature(temperature, options.temp_unit))
+                    .unwrap_or_default(),
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::tests::{current_hour, forecast_from, hourly};
+
+    #[test]
+    fn template_escapes_dollar_signs_of_the_location() {
+        let weather_data = forecast_from(
+            current_hour(),
+            (0..6).map(|hour| (hour, hourly("cloudy", 5.0))).collect(),
+        );
+        let template = weather_data.to_conky_template("$HOME", &DisplayOptions::default());
+        assert!(template.starts_with(
+            "${font :size=28}☁️${font} ${font :size=16}5.0°C${font}\n\
+             ${color grey}Cloudy — $$HOME${color}\n"
+        ));
+        // the hours after the current one
+        assert_eq!(template.matches("${alignr}5.0°C\n").count(), CONKY_HOURS);
+
+        let empty = forecast_from(current_hour(), Vec::new());
+        assert!(empty
+            .to_conky_template("$HOME", &DisplayOptions::default())
+            .is_empty());
+    }
+}
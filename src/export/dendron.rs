@@ -0,0 +1,85 @@
+use chrono::{NaiveDate, Utc};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+use super::markdown_table;
+use crate::args::DurationType;
+use crate::weather::{DisplayOptions, WeatherData};
+
+/// Length of the random note ids Dendron generates
+const DENDRON_ID_LENGTH: usize = 23;
+
+impl WeatherData {
+    /// Dendron note `weather.<place>.<date>` with the forecast of `date` as a table,
+    /// tagged with the country of `location`
+    pub fn to_dendron_frontmatter(
+        &self,
+        location: &str,
+        date: NaiveDate,
+        options: &DisplayOptions,
+    ) -> String {
+        let mut parts = location.split(',').map(str::trim);
+        let place = parts.next().unwrap_or(location);
+        let mut tags = vec!["weather".to_string(), "forecast".to_string()];
+        if let Some(country) = parts.next_back() {
+            tags.push(note_segment(country));
+        }
+
+        let id: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(DENDRON_ID_LENGTH)
+            .map(|c| char::from(c).to_ascii_lowercase())
+            .collect();
+        // when the note was written, which `--skip-hours` does not change
+        let timestamp = Utc::now().timestamp_millis();
+
+        let mut output = String::from("---\n");
+        output.push_str(&format!("id: {}\n", id));
+        output.push_str(&format!(
+            "title: weather.{}.{}\n",
+            note_segment(place),
+            date
+        ));
+        output.push_str(&format!("desc: Weather for {} on {}\n", location, date));
+        output.push_str(&format!("updated: {}\n", timestamp));
+        output.push_str(&format!("created: {}\n", timestamp));
+        output.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+        output.push_str("---\n\n");
+        output.push_str(&markdown_table(
+            self.properties
+                .timeseries_for_day(date, options.timezone)
+                .into_iter(),
+            DurationType::Today,
+            options,
+        ));
+        output
+    }
+}
+
+/// `name` as a segment of a Dendron hierarchy, where dots separate the levels
+fn note_segment(name: &str) -> String {
+    name.to_lowercase().replace(['.', ' '], "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::tests::{current_day, todays_forecast};
+
+    #[test]
+    fn note_names_are_hierarchy_segments() {
+        let date = current_day().date_naive();
+        let note = todays_forecast().to_dendron_frontmatter(
+            "St. Petersburg, Florida, United States",
+            date,
+            &DisplayOptions::default(),
+        );
+        assert!(note.contains(&format!("\ntitle: weather.st--petersburg.{}\n", date)));
+        assert!(note.contains("\ntags: [weather, forecast, united-states]\n"));
+        assert!(note.contains(&format!(
+            "\ndesc: Weather for St. Petersburg, Florida, United States on {}\n",
+            date
+        )));
+        assert_eq!(note_segment("Rio de Janeiro"), "rio-de-janeiro");
+    }
+}
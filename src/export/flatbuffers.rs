@@ -0,0 +1,175 @@
+use ::planus::Builder;
+
+use crate::weather::{Details, Summary, Timeseries, Units, WeatherData};
+
+use self::weather_fbs::weather as fbs;
+
+/// Types of `weather.fbs`, generated by planus in build.rs
+// left unformatted by planus, which trips clippy's formatting lints
+#[allow(clippy::all)]
+pub mod weather_fbs {
+    include!(concat!(env!("OUT_DIR"), "/weather_fbs.rs"));
+}
+
+impl WeatherData {
+    /// FlatBuffer laid out as `weather.fbs`, readable without deserializing the whole forecast
+    pub fn to_flatbuffer(&self) -> Vec<u8> {
+        let data = fbs::WeatherData {
+            type_: Some(self.r#type.clone()),
+            geometry_type: Some(self.geometry.r#type.clone()),
+            coordinates: Some(self.geometry.coordinates.clone()),
+            updated_at: self.properties.meta.updated_at.timestamp(),
+            units: Some(Box::new(fbs::Units::from(&self.properties.meta.units))),
+            timeseries: Some(
+                self.properties
+                    .timeseries
+                    .iter()
+                    .map(fbs::Timeseries::from)
+                    .collect(),
+            ),
+        };
+        Builder::new().finish(&data, None).to_vec()
+    }
+}
+
+impl From<&Units> for fbs::Units {
+    fn from(units: &Units) -> Self {
+        fbs::Units {
+            air_pressure_at_sea_level: units.air_pressure_at_sea_level.clone(),
+            air_temperature: units.air_temperature.clone(),
+            cloud_area_fraction: units.cloud_area_fraction.clone(),
+            precipitation_amount: units.precipitation_amount.clone(),
+            relative_humidity: units.relative_humidity.clone(),
+            wind_from_direction: units.wind_from_direction.clone(),
+            wind_speed: units.wind_speed.clone(),
+        }
+    }
+}
+
+/// Absent values stay out of the table, which is how `= null` scalars are encoded
+impl From<&Details> for fbs::Details {
+    fn from(details: &Details) -> Self {
+        fbs::Details {
+            air_pressure_at_sea_level: details.air_pressure_at_sea_level,
+            air_temperature: details.air_temperature,
+            cloud_area_fraction: details.cloud_area_fraction,
+            precipitation_amount: details.precipitation_amount,
+            relative_humidity: details.relative_humidity,
+            wind_from_direction: details.wind_from_direction,
+            wind_speed: details.wind_speed,
+        }
+    }
+}
+
+fn forecast(summary: &Summary, details: &Option<Details>) -> Box<fbs::Forecast> {
+    Box::new(fbs::Forecast {
+        symbol_code: Some(summary.symbol_code.clone()),
+        details: details
+            .as_ref()
+            .map(|details| Box::new(fbs::Details::from(details))),
+    })
+}
+
+impl From<&Timeseries> for fbs::Timeseries {
+    fn from(timeseries: &Timeseries) -> Self {
+        let data = &timeseries.data;
+        fbs::Timeseries {
+            time: timeseries.time.timestamp(),
+            instant: Some(Box::new(fbs::Details::from(&data.instant.details))),
+            next_1_hours: data
+                .next_1_hours
+                .as_ref()
+                .map(|next| forecast(&next.summary, &next.details)),
+            next_6_hours: data
+                .next_6_hours
+                .as_ref()
+                .map(|next| forecast(&next.summary, &next.details)),
+            next_12_hours: data
+                .next_12_hours
+                .as_ref()
+                .map(|next| forecast(&next.summary, &next.details)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::planus::ReadAsRoot;
+
+    use super::fbs;
+    use crate::weather::WeatherData;
+
+    const FORECAST: &str = r#"{
+        "type": "Feature",
+        "geometry": { "type": "Point", "coordinates": [10.75, 59.91, 0] },
+        "properties": {
+            "meta": {
+                "updated_at": "2026-10-16T17:37:00Z",
+                "units": { "air_temperature": "celsius", "wind_speed": "m/s" }
+            },
+            "timeseries": [
+                {
+                    "time": "2026-10-16T18:00:00Z",
+                    "data": {
+                        "instant": { "details": { "air_temperature": 24.7, "wind_speed": 6.7 } },
+                        "next_1_hours": {
+                            "summary": { "symbol_code": "heavyrainandthunder" },
+                            "details": { "precipitation_amount": 2.4 }
+                        },
+                        "next_12_hours": { "summary": { "symbol_code": "cloudy" } }
+                    }
+                },
+                {
+                    "time": "2026-10-16T19:00:00Z",
+                    "data": { "instant": { "details": { "air_temperature": -3.5 } } }
+                }
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn to_flatbuffer_reads_back_with_the_generated_accessors() {
+        let data: WeatherData = serde_json::from_str(FORECAST).unwrap();
+        let bytes = data.to_flatbuffer();
+        let root = fbs::WeatherDataRef::read_as_root(&bytes).unwrap();
+
+        assert_eq!(root.type_().unwrap(), Some("Feature"));
+        assert_eq!(root.geometry_type().unwrap(), Some("Point"));
+        let coordinates: Vec<f64> = root.coordinates().unwrap().unwrap().iter().collect();
+        assert_eq!(coordinates, [10.75, 59.91, 0.0]);
+        assert_eq!(root.updated_at().unwrap(), data.properties.meta.updated_at.timestamp());
+
+        let units = root.units().unwrap().unwrap();
+        assert_eq!(units.air_temperature().unwrap(), Some("celsius"));
+        assert_eq!(units.wind_speed().unwrap(), Some("m/s"));
+        assert_eq!(units.air_pressure_at_sea_level().unwrap(), None);
+
+        let timeseries: Vec<_> = root
+            .timeseries()
+            .unwrap()
+            .unwrap()
+            .iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(timeseries.len(), 2);
+        assert_eq!(timeseries[0].time().unwrap(), data.properties.timeseries[0].time.timestamp());
+
+        let instant = timeseries[0].instant().unwrap().unwrap();
+        assert_eq!(instant.air_temperature().unwrap(), Some(24.7));
+        assert_eq!(instant.wind_speed().unwrap(), Some(6.7));
+        assert_eq!(instant.relative_humidity().unwrap(), None);
+
+        let next_hour = timeseries[0].next_1_hours().unwrap().unwrap();
+        assert_eq!(next_hour.symbol_code().unwrap(), Some("heavyrainandthunder"));
+        let details = next_hour.details().unwrap().unwrap();
+        assert_eq!(details.precipitation_amount().unwrap(), Some(2.4));
+        assert!(timeseries[0].next_6_hours().unwrap().is_none());
+        let next_12_hours = timeseries[0].next_12_hours().unwrap().unwrap();
+        assert_eq!(next_12_hours.symbol_code().unwrap(), Some("cloudy"));
+        assert!(next_12_hours.details().unwrap().is_none());
+
+        let instant = timeseries[1].instant().unwrap().unwrap();
+        assert_eq!(instant.air_temperature().unwrap(), Some(-3.5));
+        assert!(timeseries[1].next_1_hours().unwrap().is_none());
+    }
+}
@@ -0,0 +1,53 @@
+use super::markdown_table;
+use crate::args::DurationType;
+use crate::weather::{display_time, format_header, DisplayOptions, WeatherData};
+
+impl WeatherData {
+    /// Pandoc Markdown of the forecast with YAML front matter, for `pandoc -o weather.epub`
+    pub fn to_kindle_markdown(
+        &self,
+        duration: DurationType,
+        location: &str,
+        options: &DisplayOptions,
+    ) -> String {
+        let now = options.now();
+        let local = display_time(now, options);
+        let title = format_header(duration, location, local);
+        // e-readers rarely ship an emoji font
+        let options = DisplayOptions {
+            no_emoji: true,
+            ..options.clone()
+        };
+        let mut output = format!(
+            "---\ntitle: \"{}\"\ndate: {}\nauthor: Weather-Cli\n---\n\n",
+            title.trim_end().replace('"', "\\\""),
+            local.format("%Y-%m-%d"),
+        );
+        output.push_str(&markdown_table(
+            self.lazy_timeseries(duration, now, options.timezone),
+            duration,
+            &options,
+        ));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::tests::{current_hour, forecast_from, hourly};
+
+    #[test]
+    fn title_quotes_are_escaped_for_yaml() {
+        let weather_data = forecast_from(current_hour(), vec![(0, hourly("cloudy", 5.0))]);
+        let markdown = weather_data.to_kindle_markdown(
+            DurationType::Now,
+            "The \"Big\" Apple",
+            &DisplayOptions::default(),
+        );
+        assert!(markdown.starts_with("---\ntitle: \"Weather for The \\\"Big\\\" Apple at "));
+        assert!(markdown.contains("\nauthor: Weather-Cli\n---\n\n| Time |"));
+        // without emoji, which e-readers cannot show
+        assert!(markdown.contains(" | Cloudy | 5.0°C |"));
+    }
+}
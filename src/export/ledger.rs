@@ -0,0 +1,116 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::args::DurationType;
+use crate::weather::{
+    describe_weather, display_time, format_temperature, local_day_bounds, weekend_start,
+    DisplayOptions, WeatherData,
+};
+
+impl WeatherData {
+    /// Ledger / hledger comment with the midday weather of `date`,
+    /// e.g. `; weather: Oslo, 22.0°C, ☀️ Clear Sky (Day)`
+    pub fn to_ledger_comment_block(
+        &self,
+        date: NaiveDate,
+        location: &str,
+        options: &DisplayOptions,
+    ) -> String {
+        let noon = local_day_bounds(date, options.timezone).0 + Duration::hours(12);
+
+        let Some(timeseries) = self
+            .properties
+            .timeseries_for_day(date, options.timezone)
+            .into_iter()
+            .min_by_key(|timeseries| (timeseries.time - noon).num_minutes().abs())
+        else {
+            return String::new();
+        };
+
+        let mut fields = vec![location.to_string()];
+        if let Some(temperature) = timeseries.details().air_temperature {
+            fields.push(format_temperature(temperature, options.temp_unit));
+        }
+        // entries a few days ahead only come with the 6 and 12 hour summaries
+        let summary = timeseries
+            .summary(DurationType::Today)
+            .or_else(|_| timeseries.summary(DurationType::Week));
+        if let Ok(summary) = summary {
+            fields.push(describe_weather(&summary.symbol_code, options));
+        }
+
+        format!("; weather: {}\n", fields.join(", "))
+    }
+
+    /// Ledger comments for every day covered by `duration`, each day preceded by its date
+    pub(crate) fn display_ledger_comment(
+        &self,
+        duration: DurationType,
+        location: &str,
+        options: &DisplayOptions,
+    ) -> String {
+        let today = display_time(options.now(), options).date_naive();
+        let days = match duration {
+            DurationType::Tomorrow => 1..2,
+            DurationType::Week => 0..7,
+            DurationType::Weekend => {
+                let saturday = (weekend_start(today) - today).num_days();
+                saturday..saturday + 2
+            }
+            DurationType::Custom { start, end } => {
+                (start - today).num_days()..(end - today).num_days() + 1
+            }
+            _ => 0..1,
+        };
+
+        days.map(|offset| today + Duration::days(offset))
+            .map(|date| (date, self.to_ledger_comment_block(date, location, options)))
+            .filter(|(_, block)| !block.is_empty())
+            .map(|(date, block)| format!("; {}\n{}", date, block))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::tests::{current_day, forecast_from, six_hourly};
+
+    #[test]
+    fn comments_cover_the_days_of_custom_ranges_and_the_weekend() {
+        let yesterday = current_day() - Duration::days(1);
+        let weather_data = forecast_from(
+            yesterday,
+            (0..40)
+                .map(|block| (6 * block, six_hourly("cloudy", 0.0, 5.0)))
+                .collect(),
+        );
+        let options = DisplayOptions::default();
+        let today = current_day().date_naive();
+        let days = |duration| {
+            weather_data
+                .display_ledger_comment(duration, "Oslo", &options)
+                .lines()
+                .filter_map(|line| line.strip_prefix("; ")?.parse::<NaiveDate>().ok())
+                .collect::<Vec<_>>()
+        };
+
+        let start = today + Duration::days(2);
+        let end = today + Duration::days(4);
+        assert_eq!(
+            days(DurationType::Custom { start, end }),
+            vec![start, start + Duration::days(1), end]
+        );
+        let saturday = weekend_start(today);
+        assert_eq!(
+            days(DurationType::Weekend),
+            vec![saturday, saturday + Duration::days(1)]
+        );
+
+        let comment = weather_data.display_ledger_comment(DurationType::Today, "Oslo", &options);
+        assert_eq!(
+            comment,
+            format!("; {}\n; weather: Oslo, 5.0°C, ☁️ Cloudy\n", today)
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use crate::args::DurationType;
+use crate::weather::{
+    describe_weather, display_time, entry_time, entry_time_format, format_header, format_speed,
+    format_temperature, DisplayOptions, WeatherData,
+};
+
+impl WeatherData {
+    /// Logseq outline: the header block, a child block per forecast time and a grandchild
+    /// block per measurement
+    pub fn to_logseq_blocks(
+        &self,
+        duration: DurationType,
+        location: &str,
+        options: &DisplayOptions,
+    ) -> String {
+        let now = options.now();
+        let time_format = entry_time_format(duration);
+
+        // Logseq indents child blocks with tabs
+        let header = format_header(duration, location, display_time(now, options));
+        let mut output = format!("- {}\n", header.trim_end());
+        for timeseries in self.lazy_timeseries(duration, now, options.timezone) {
+            let time = entry_time(timeseries, options);
+            output.push_str(&format!("\t- {}", time.format(time_format)));
+            if let Ok(summary) = timeseries.summary(duration) {
+                output.push_str(&format!(
+                    " {}",
+                    describe_weather(&summary.symbol_code, options)
+                ));
+            }
+            output.push('\n');
+
+            let details = timeseries.details();
+            let measurements = [
+                (
+                    "Temperature",
+                    details
+                        .air_temperature
+                        .map(|temperature| format_temperature(temperature, options.temp_unit)),
+                ),
+                (
+                    "Wind",
+                    details
+                        .wind_speed
+                        .map(|wind_speed| format_speed(wind_speed, options.speed_unit)),
+                ),
+                (
+                    "Humidity",
+                    details
+                        .relative_humidity
+                        .map(|humidity| format!("{}%", humidity)),
+                ),
+                (
+                    "Precipitation",
+                    timeseries
+                        .precipitation_amount(duration)
+                        .map(|amount| format!("{} mm", amount)),
+                ),
+            ];
+            for (name, value) in measurements {
+                if let Some(value) = value {
+                    output.push_str(&format!("\t\t- {}: {}\n", name, value));
+                }
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::tests::todays_forecast;
+
+    #[test]
+    fn blocks_nest_measurements_under_their_time() {
+        let blocks = todays_forecast().to_logseq_blocks(
+            DurationType::Today,
+            "Oslo",
+            &DisplayOptions::default(),
+        );
+        assert!(blocks.starts_with("- Weather for Oslo on "));
+        assert!(blocks.contains("\n\t- 01:00 ☁️ Cloudy\n\t\t- Temperature: 1.0°C\n\t- 02:00 "));
+        assert_eq!(blocks.lines().count(), 1 + 24 * 2);
+    }
+}
@@ -0,0 +1,90 @@
+//! Conversions of `WeatherData` into formats consumed by other tools; those needing extra
+//! dependencies are behind their own feature
+
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "avro")]
+mod avro;
+#[cfg(feature = "capnp")]
+mod capnp;
+#[cfg(feature = "cbor")]
+mod cbor;
+mod conky;
+mod dendron;
+#[cfg(feature = "flatbuffers")]
+mod flatbuffers;
+mod kindle;
+mod ledger;
+mod logseq;
+#[cfg(feature = "mqtt")]
+mod mqtt;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod notion;
+mod obsidian;
+mod orgzly;
+#[cfg(feature = "polars")]
+mod polars;
+mod prometheus;
+#[cfg(feature = "protobuf")]
+pub(crate) mod protobuf;
+mod roam;
+
+#[cfg(feature = "capnp")]
+pub use self::capnp::weather_capnp;
+#[cfg(feature = "flatbuffers")]
+pub use self::flatbuffers::weather_fbs;
+#[cfg(feature = "protobuf")]
+pub use self::protobuf::proto;
+
+use crate::args::DurationType;
+use crate::weather::{
+    describe_weather, entry_time, entry_time_format, format_speed, format_temperature,
+    DisplayOptions, Timeseries,
+};
+
+/// Pipe table of the forecast, understood by Pandoc and most Markdown note apps
+fn markdown_table<'a>(
+    timeseries: impl Iterator<Item = &'a Timeseries>,
+    duration: DurationType,
+    options: &DisplayOptions,
+) -> String {
+    let mut table = String::from("| Time | Weather | Temperature | Wind | Humidity |\n");
+    table.push_str("|:-----|:--------|------------:|-----:|---------:|\n");
+    table.push_str(&table_rows(timeseries, duration, options));
+    table
+}
+
+/// Rows of the forecast table, the same in Markdown and Org-mode
+fn table_rows<'a>(
+    timeseries: impl Iterator<Item = &'a Timeseries>,
+    duration: DurationType,
+    options: &DisplayOptions,
+) -> String {
+    let time_format = entry_time_format(duration);
+    let mut rows = String::new();
+    for timeseries in timeseries {
+        let details = timeseries.details();
+        rows.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            entry_time(timeseries, options).format(time_format),
+            timeseries
+                .summary(duration)
+                .map(|summary| describe_weather(&summary.symbol_code, options))
+                .unwrap_or_default(),
+            details
+                .air_temperature
+                .map(|temperature| format_temperature(temperature, options.temp_unit))
+                .unwrap_or_default(),
+            details
+                .wind_speed
+                .map(|wind_speed| format_speed(wind_speed, options.speed_unit))
+                .unwrap_or_default(),
+            details
+                .relative_humidity
+                .map(|humidity| format!("{}%", humidity))
+                .unwrap_or_default(),
+        ));
+    }
+    rows
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use rumqttc::{AsyncClient, Event, MqttOptions, Outgoing, Packet, QoS};
+use url::Url;
+
+use crate::args::DurationType;
+use crate::weather::{WeatherData, WeatherError};
+
+const DEFAULT_MQTT_PORT: u16 = 1883;
+
+impl WeatherData {
+    /// Current conditions as `(topic, value)` pairs, one topic per field under `topic_prefix`
+    pub fn to_mqtt_payload(&self, topic_prefix: &str) -> Vec<(String, String)> {
+        let Some(timeseries) = self
+            .lazy_timeseries(DurationType::Now, Utc::now(), None)
+            .next()
+        else {
+            return Vec::new();
+        };
+        let details = timeseries.details();
+        let values = [
+            ("temperature", details.air_temperature),
+            ("wind_speed", details.wind_speed),
+            ("wind_from_direction", details.wind_from_direction),
+            ("relative_humidity", details.relative_humidity),
+            ("cloud_area_fraction", details.cloud_area_fraction),
+            (
+                "air_pressure_at_sea_level",
+                details.air_pressure_at_sea_level,
+            ),
+            (
+                "precipitation_amount",
+                timeseries.precipitation_amount(DurationType::Now),
+            ),
+        ];
+
+        let mut payload: Vec<(String, String)> = values
+            .into_iter()
+            .filter_map(|(field, value)| {
+                Some((format!("{}/{}", topic_prefix, field), value?.to_string()))
+            })
+            .collect();
+        if let Ok(summary) = timeseries.summary(DurationType::Now) {
+            payload.push((
+                format!("{}/symbol_code", topic_prefix),
+                summary.symbol_code.clone(),
+            ));
+        }
+        payload
+    }
+
+    /// Publishes `to_mqtt_payload` to the broker at `broker`, e.g. `mqtt://localhost:1883`,
+    /// giving up when the broker hasn't acknowledged every message within `timeout`
+    pub async fn publish_mqtt(
+        &self,
+        broker: &str,
+        topic_prefix: &str,
+        timeout: Duration,
+    ) -> Result<(), WeatherError> {
+        let export_error = |e: &dyn std::fmt::Display| WeatherError::Export(e.to_string());
+
+        let url = Url::parse(broker).map_err(|e| export_error(&e))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| export_error(&format!("no host in MQTT broker URL '{}'", broker)))?;
+        let options =
+            MqttOptions::new("weather-cli", host, url.port().unwrap_or(DEFAULT_MQTT_PORT));
+
+        let payload = self.to_mqtt_payload(topic_prefix);
+        // the request queue holds every message, so publishing never waits on the event loop
+        let (client, mut eventloop) = AsyncClient::new(options, payload.len() + 1);
+        for (topic, value) in &payload {
+            client
+                .publish(topic, QoS::AtLeastOnce, false, value.clone())
+                .await
+                .map_err(|e| export_error(&e))?;
+        }
+
+        let acknowledgements = async {
+            let mut acknowledged = 0;
+            while acknowledged < payload.len() {
+                if let Event::Incoming(Packet::PubAck(_)) = eventloop.poll().await? {
+                    acknowledged += 1;
+                }
+            }
+            Ok::<_, rumqttc::ConnectionError>(())
+        };
+        tokio::time::timeout(timeout, acknowledgements)
+            .await
+            .map_err(|_| {
+                export_error(&format!(
+                    "MQTT broker '{}' did not acknowledge within {}s",
+                    broker,
+                    timeout.as_secs()
+                ))
+            })?
+            .map_err(|e| export_error(&e))?;
+
+        client.disconnect().await.map_err(|e| export_error(&e))?;
+        // every message is acknowledged by now, so a broker slow to close is no error
+        let _ = tokio::time::timeout(timeout, async {
+            while let Ok(event) = eventloop.poll().await {
+                if let Event::Outgoing(Outgoing::Disconnect) = event {
+                    break;
+                }
+            }
+        })
+        .await;
+        Ok(())
+    }
+}
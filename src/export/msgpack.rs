@@ -0,0 +1,12 @@
+use crate::weather::WeatherData;
+
+impl WeatherData {
+    /// MessagePack encoding with named fields, more compact and faster to parse than JSON
+    pub fn to_msgpack(&self) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+        rmp_serde::to_vec_named(self)
+    }
+
+    pub fn from_msgpack(bytes: &[u8]) -> Result<WeatherData, rmp_serde::decode::Error> {
+        rmp_serde::from_slice(bytes)
+    }
+}
@@ -0,0 +1,82 @@
+use super::markdown_table;
+use crate::args::DurationType;
+use crate::weather::{
+    describe_weather, display_time, format_header, format_speed, format_temperature, DisplayOptions,
+    Timeseries, WeatherData,
+};
+
+impl WeatherData {
+    /// Markdown for Notion's importer, current conditions as a callout above the forecast table
+    pub fn to_notion_markdown(
+        &self,
+        duration: DurationType,
+        location: &str,
+        options: &DisplayOptions,
+    ) -> String {
+        let now = options.now();
+        let mut output = format!(
+            "# {}\n\n",
+            format_header(duration, location, display_time(now, options)).trim_end()
+        );
+
+        if let Some(current) = self
+            .lazy_timeseries(DurationType::Now, now, options.timezone)
+            .next()
+        {
+            if let Ok(summary) = current.summary(DurationType::Now) {
+                // Notion turns a quote starting with an emoji into a callout using that icon
+                let description = describe_weather(&summary.symbol_code, options);
+                let callout = match description.split_once(' ') {
+                    Some((emoji, text)) if !options.no_emoji => format!("{} **{}**", emoji, text),
+                    _ => format!("**{}**", description),
+                };
+                output.push_str(&format!("> {} — {}\n", callout, location));
+            }
+            let details = current.details();
+            if let Some(temperature) = details.air_temperature {
+                output.push_str(&format!(
+                    "> {}",
+                    format_temperature(temperature, options.temp_unit)
+                ));
+                if let Some(wind_speed) = details.wind_speed {
+                    output.push_str(&format!(
+                        ", wind {}",
+                        format_speed(wind_speed, options.speed_unit)
+                    ));
+                }
+                if let Some(humidity) = details.relative_humidity {
+                    output.push_str(&format!(", humidity {}%", humidity));
+                }
+                output.push('\n');
+            }
+            output.push('\n');
+        }
+
+        let timeseries: Vec<&Timeseries> = self
+            .lazy_timeseries(duration, now, options.timezone)
+            .collect();
+        output.push_str(&markdown_table(timeseries.into_iter(), duration, options));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::tests::{current_hour, forecast_from, hourly};
+
+    #[test]
+    fn callout_starts_with_the_condition_emoji() {
+        let weather_data = forecast_from(current_hour(), vec![(0, hourly("cloudy", 5.0))]);
+        let markdown =
+            weather_data.to_notion_markdown(DurationType::Now, "Oslo", &DisplayOptions::default());
+        assert!(markdown.contains("\n\n> ☁️ **Cloudy** — Oslo\n> 5.0°C\n\n| Time |"));
+
+        let options = DisplayOptions {
+            no_emoji: true,
+            ..DisplayOptions::default()
+        };
+        let markdown = weather_data.to_notion_markdown(DurationType::Now, "Oslo", &options);
+        assert!(markdown.contains("\n\n> **Cloudy** — Oslo\n"));
+    }
+}
@@ -0,0 +1,98 @@
+use super::markdown_table;
+use crate::args::DurationType;
+use crate::weather::{
+    convert_temperature, describe_weather, display_time, entry_time, format_header, DisplayOptions,
+    Timeseries, WeatherData,
+};
+
+impl WeatherData {
+    /// Obsidian note with the forecast summary as YAML frontmatter properties
+    pub fn to_obsidian_note(
+        &self,
+        duration: DurationType,
+        location: &str,
+        options: &DisplayOptions,
+    ) -> String {
+        let now = options.now();
+        let local = display_time(now, options);
+        let timeseries: Vec<&Timeseries> = self
+            .lazy_timeseries(duration, now, options.timezone)
+            .collect();
+        let temperatures = timeseries
+            .iter()
+            .filter_map(|timeseries| timeseries.details().air_temperature);
+
+        let mut frontmatter = vec![
+            (
+                "weather_location",
+                format!("\"{}\"", location.replace('"', "\\\"")),
+            ),
+            (
+                "weather_date",
+                timeseries
+                    .first()
+                    .map_or(local, |timeseries| entry_time(timeseries, options))
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            ),
+        ];
+        // numbers, so Dataview can compare them, in the unit of `--temp-unit`
+        if let Some(min) = temperatures.clone().reduce(f64::min) {
+            let min = convert_temperature(min, options.temp_unit);
+            frontmatter.push(("weather_temp_min", format!("{:.1}", min)));
+        }
+        if let Some(max) = temperatures.reduce(f64::max) {
+            let max = convert_temperature(max, options.temp_unit);
+            frontmatter.push(("weather_temp_max", format!("{:.1}", max)));
+        }
+        if let Some(summary) = timeseries
+            .first()
+            .and_then(|timeseries| timeseries.summary(duration).ok())
+        {
+            // plain text, so the property can be searched and filtered on
+            let plain = DisplayOptions {
+                no_emoji: true,
+                ..options.clone()
+            };
+            frontmatter.push((
+                "weather_condition",
+                describe_weather(&summary.symbol_code, &plain),
+            ));
+        }
+
+        let mut output = String::from("---\n");
+        for (name, value) in frontmatter {
+            output.push_str(&format!("{}: {}\n", name, value));
+        }
+        output.push_str("---\n\n");
+        output.push_str(&format!(
+            "# {}\n\n",
+            format_header(duration, location, local).trim_end()
+        ));
+        output.push_str(&markdown_table(timeseries.into_iter(), duration, options));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::TempUnit;
+    use crate::weather::tests::{current_day, todays_forecast};
+
+    #[test]
+    fn frontmatter_has_plain_properties() {
+        let options = DisplayOptions {
+            temp_unit: TempUnit::Fahrenheit,
+            ..DisplayOptions::default()
+        };
+        let note = todays_forecast().to_obsidian_note(DurationType::Today, "\"Oslo\"", &options);
+        let frontmatter = format!(
+            "---\nweather_location: \"\\\"Oslo\\\"\"\nweather_date: {}\n\
+             weather_temp_min: 32.0\nweather_temp_max: 73.4\nweather_condition: Cloudy\n---\n\n",
+            current_day().format("%Y-%m-%d")
+        );
+        assert!(note.starts_with(&frontmatter), "{}", note);
+        assert!(note.contains("\n# Weather for \"Oslo\" on "));
+    }
+}
@@ -0,0 +1,81 @@
+use super::table_rows;
+use crate::args::DurationType;
+use crate::weather::{
+    display_time, format_header, format_speed, format_temperature, DisplayOptions, Timeseries,
+    WeatherData,
+};
+
+impl WeatherData {
+    /// Org-mode heading for Orgzly with the key metrics in its property drawer and an hourly table
+    pub fn to_orgzly_note(
+        &self,
+        duration: DurationType,
+        location: &str,
+        options: &DisplayOptions,
+    ) -> String {
+        let now = options.now();
+        let local = display_time(now, options);
+        let timeseries: Vec<&Timeseries> = self
+            .lazy_timeseries(duration, now, options.timezone)
+            .collect();
+
+        let mut output = format!("* {}\n", format_header(duration, location, local).trim_end());
+        if duration == DurationType::Today {
+            output.push_str(&format!("  SCHEDULED: <{}>\n", local.format("%Y-%m-%d %a")));
+        }
+
+        let temperatures = timeseries
+            .iter()
+            .filter_map(|timeseries| timeseries.details().air_temperature);
+        let wind_speeds = timeseries
+            .iter()
+            .filter_map(|timeseries| timeseries.details().wind_speed);
+        let mut properties = vec![
+            ("LOCATION", location.to_string()),
+            ("UPDATED", self.properties.meta.updated_at.to_rfc3339()),
+        ];
+        if let Some(high) = temperatures.clone().reduce(f64::max) {
+            properties.push(("HIGH", format_temperature(high, options.temp_unit)));
+        }
+        if let Some(low) = temperatures.reduce(f64::min) {
+            properties.push(("LOW", format_temperature(low, options.temp_unit)));
+        }
+        if let Some(wind_speed) = wind_speeds.reduce(f64::max) {
+            properties.push(("MAX_WIND", format_speed(wind_speed, options.speed_unit)));
+        }
+
+        output.push_str("  :PROPERTIES:\n");
+        for (name, value) in properties {
+            output.push_str(&format!("  :{}: {}\n", name, value));
+        }
+        output.push_str("  :END:\n\n");
+
+        output.push_str("| Time | Weather | Temperature | Wind | Humidity |\n");
+        output.push_str("|------+---------+-------------+------+----------|\n");
+        output.push_str(&table_rows(timeseries.into_iter(), duration, options));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::tests::{current_day, todays_forecast};
+
+    #[test]
+    fn note_has_the_key_metrics_as_properties() {
+        let note = todays_forecast().to_orgzly_note(
+            DurationType::Today,
+            "Oslo",
+            &DisplayOptions::default(),
+        );
+        assert!(note.starts_with("* Weather for Oslo on "));
+        assert!(note.contains(&format!(
+            "\n  SCHEDULED: <{}>\n",
+            current_day().format("%Y-%m-%d %a")
+        )));
+        assert!(note.contains("\n  :LOCATION: Oslo\n"));
+        assert!(note.contains("\n  :HIGH: 23.0°C\n  :LOW: 0.0°C\n  :END:\n"));
+        assert!(note.contains("\n| 00:00 | ☁️ Cloudy | 0.0°C |  |  |\n"));
+    }
+}
@@ -0,0 +1,78 @@
+use ::polars::prelude::*;
+use chrono::Utc;
+
+use crate::args::DurationType;
+use crate::weather::{Timeseries, WeatherData, WeatherError};
+
+impl WeatherData {
+    /// DataFrame of the timeseries covered by `duration`, with the `time` column in UTC
+    pub fn into_polars_dataframe(&self, duration: DurationType) -> DataFrame {
+        let timeseries: Vec<&Timeseries> = self
+            .lazy_timeseries(duration, Utc::now(), None)
+            .collect();
+
+        let time = Column::new(
+            "time".into(),
+            timeseries
+                .iter()
+                .map(|timeseries| timeseries.time.timestamp_millis())
+                .collect::<Vec<_>>(),
+        )
+        .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+        .expect("timestamps can be cast to datetime");
+
+        let float_column = |name: &str, value: fn(&Timeseries) -> Option<f64>| {
+            Column::new(
+                name.into(),
+                timeseries
+                    .iter()
+                    .map(|timeseries| value(timeseries))
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        DataFrame::new(vec![
+            time,
+            float_column("temperature", |timeseries| {
+                timeseries.details().air_temperature
+            }),
+            float_column("wind_speed", |timeseries| timeseries.details().wind_speed),
+            float_column("relative_humidity", |timeseries| {
+                timeseries.details().relative_humidity
+            }),
+            Column::new(
+                "precipitation".into(),
+                timeseries
+                    .iter()
+                    .map(|timeseries| timeseries.precipitation_amount(duration))
+                    .collect::<Vec<_>>(),
+            ),
+            Column::new(
+                "symbol_code".into(),
+                timeseries
+                    .iter()
+                    .map(|timeseries| {
+                        timeseries
+                            .summary(duration)
+                            .ok()
+                            .map(|summary| summary.symbol_code.as_str())
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+        ])
+        .expect("all columns have one value per timeseries entry")
+    }
+
+    pub(crate) fn display_polars_csv(
+        &self,
+        duration: DurationType,
+    ) -> Result<String, WeatherError> {
+        let mut dataframe = self.into_polars_dataframe(duration);
+        let mut output = Vec::new();
+        CsvWriter::new(&mut output)
+            .finish(&mut dataframe)
+            .map_err(|e| WeatherError::Export(e.to_string()))?;
+
+        Ok(String::from_utf8(output).expect("CSV output is valid UTF-8"))
+    }
+}
@@ -0,0 +1,100 @@
+use crate::args::DurationType;
+use crate::weather::{DisplayOptions, WeatherData};
+
+impl WeatherData {
+    /// Current conditions, or those `--skip-hours` ahead, as gauges in the Prometheus text
+    /// exposition format (version 0.0.4); the values stay in the SI units of the metric names
+    pub fn to_prometheus_metrics(&self, location: &str, options: &DisplayOptions) -> String {
+        let label = format!(
+            "{{location=\"{}\"}}",
+            location
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+        );
+
+        let mut metrics = Vec::new();
+        if let Some(timeseries) = self
+            .lazy_timeseries(DurationType::Now, options.now(), options.timezone)
+            .next()
+        {
+            let details = timeseries.details();
+            metrics.extend([
+                (
+                    "weather_temperature_celsius",
+                    "Air temperature",
+                    details.air_temperature,
+                ),
+                (
+                    "weather_wind_speed_meters_per_second",
+                    "Wind speed",
+                    details.wind_speed,
+                ),
+                (
+                    "weather_wind_from_direction_degrees",
+                    "Direction the wind blows from",
+                    details.wind_from_direction,
+                ),
+                (
+                    "weather_relative_humidity_percent",
+                    "Relative humidity",
+                    details.relative_humidity,
+                ),
+                (
+                    "weather_cloud_area_fraction_percent",
+                    "Share of the sky covered by clouds",
+                    details.cloud_area_fraction,
+                ),
+                (
+                    "weather_air_pressure_at_sea_level_hectopascals",
+                    "Air pressure at sea level",
+                    details.air_pressure_at_sea_level,
+                ),
+            ]);
+        }
+        metrics.push((
+            "weather_api_updated_timestamp_seconds",
+            "Time at which met.no last updated the forecast",
+            Some(self.properties.meta.updated_at.timestamp() as f64),
+        ));
+
+        let mut output = String::new();
+        for (name, help, value) in metrics {
+            if let Some(value) = value {
+                output.push_str(&format!("# HELP {} {}\n", name, help));
+                output.push_str(&format!("# TYPE {} gauge\n", name));
+                output.push_str(&format!("{}{} {}\n", name, label, value));
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::tests::{current_hour, forecast_from, hourly};
+
+    #[test]
+    fn metrics_escape_the_location_and_honour_skip_hours() {
+        let weather_data = forecast_from(
+            current_hour(),
+            vec![(0, hourly("cloudy", 5.0)), (24, hourly("cloudy", 15.0))],
+        );
+        let metrics =
+            weather_data.to_prometheus_metrics("\\a \"b\"\nc", &DisplayOptions::default());
+        assert!(
+            metrics.contains("weather_temperature_celsius{location=\"\\\\a \\\"b\\\"\\nc\"} 5\n")
+        );
+        assert!(metrics.contains("# TYPE weather_temperature_celsius gauge\n"));
+        // no wind in the forecast, so no wind gauge
+        assert!(!metrics.contains("weather_wind_speed"));
+
+        let options = DisplayOptions {
+            skip_hours: 24,
+            ..DisplayOptions::default()
+        };
+        let metrics = weather_data.to_prometheus_metrics("Oslo", &options);
+        assert!(metrics.contains("weather_temperature_celsius{location=\"Oslo\"} 15\n"));
+    }
+}
@@ -0,0 +1,247 @@
+use chrono::{DateTime, Utc};
+use prost::Message;
+
+use crate::weather::{
+    Data, Details, Geometry, Instant, Meta, Next12Hours, Next1Hour, Next6Hours, Properties,
+    Summary, Timeseries, Units, WeatherData,
+};
+
+/// Message types of `weather.proto`, generated by build.rs with prost-build
+pub mod proto {
+    include!(concat!(env!("OUT_DIR"), "/weather.rs"));
+}
+
+impl WeatherData {
+    pub fn to_protobuf(&self) -> Result<Vec<u8>, prost::EncodeError> {
+        let message = proto::WeatherData::from(self);
+        let mut buffer = Vec::with_capacity(message.encoded_len());
+        message.encode(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    pub fn from_protobuf(bytes: &[u8]) -> Result<WeatherData, prost::DecodeError> {
+        proto::WeatherData::decode(bytes).map(WeatherData::from)
+    }
+}
+
+fn from_timestamp(seconds: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(seconds, 0).unwrap_or_default()
+}
+
+fn to_forecast(summary: &Summary, details: &Option<Details>) -> proto::Forecast {
+    proto::Forecast {
+        symbol_code: summary.symbol_code.clone(),
+        details: details.as_ref().map(proto::Details::from),
+    }
+}
+
+fn from_forecast(forecast: proto::Forecast) -> (Summary, Option<Details>) {
+    (
+        Summary {
+            symbol_code: forecast.symbol_code,
+        },
+        forecast.details.map(Details::from),
+    )
+}
+
+impl From<&WeatherData> for proto::WeatherData {
+    fn from(data: &WeatherData) -> Self {
+        proto::WeatherData {
+            r#type: data.r#type.clone(),
+            geometry: Some(proto::Geometry {
+                r#type: data.geometry.r#type.clone(),
+                coordinates: data.geometry.coordinates.clone(),
+            }),
+            properties: Some(proto::Properties {
+                meta: Some(proto::Meta {
+                    updated_at: data.properties.meta.updated_at.timestamp(),
+                    units: Some(proto::Units::from(&data.properties.meta.units)),
+                }),
+                timeseries: data
+                    .properties
+                    .timeseries
+                    .iter()
+                    .map(proto::Timeseries::from)
+                    .collect(),
+            }),
+        }
+    }
+}
+
+impl From<proto::WeatherData> for WeatherData {
+    fn from(data: proto::WeatherData) -> Self {
+        let geometry = data.geometry.unwrap_or_default();
+        let properties = data.properties.unwrap_or_default();
+        let meta = properties.meta.unwrap_or_default();
+
+        WeatherData {
+            r#type: data.r#type,
+            geometry: Geometry {
+                r#type: geometry.r#type,
+                coordinates: geometry.coordinates,
+            },
+            properties: Properties {
+                meta: Meta {
+                    updated_at: from_timestamp(meta.updated_at),
+                    units: Units::from(meta.units.unwrap_or_default()),
+                },
+                timeseries: properties
+                    .timeseries
+                    .into_iter()
+                    .map(Timeseries::from)
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl From<&Timeseries> for proto::Timeseries {
+    fn from(timeseries: &Timeseries) -> Self {
+        let data = &timeseries.data;
+        proto::Timeseries {
+            time: timeseries.time.timestamp(),
+            instant: Some(proto::Details::from(&data.instant.details)),
+            next_1_hours: data
+                .next_1_hours
+                .as_ref()
+                .map(|next| to_forecast(&next.summary, &next.details)),
+            next_6_hours: data
+                .next_6_hours
+                .as_ref()
+                .map(|next| to_forecast(&next.summary, &next.details)),
+            next_12_hours: data
+                .next_12_hours
+                .as_ref()
+                .map(|next| to_forecast(&next.summary, &next.details)),
+        }
+    }
+}
+
+impl From<proto::Timeseries> for Timeseries {
+    fn from(timeseries: proto::Timeseries) -> Self {
+        Timeseries {
+            time: from_timestamp(timeseries.time),
+            data: Data {
+                instant: Instant {
+                    details: Details::from(timeseries.instant.unwrap_or_default()),
+                },
+                next_1_hours: timeseries.next_1_hours.map(|forecast| {
+                    let (summary, details) = from_forecast(forecast);
+                    Next1Hour { summary, details }
+                }),
+                next_6_hours: timeseries.next_6_hours.map(|forecast| {
+                    let (summary, details) = from_forecast(forecast);
+                    Next6Hours { summary, details }
+                }),
+                next_12_hours: timeseries.next_12_hours.map(|forecast| {
+                    let (summary, details) = from_forecast(forecast);
+                    Next12Hours { summary, details }
+                }),
+            },
+        }
+    }
+}
+
+impl From<&Units> for proto::Units {
+    fn from(units: &Units) -> Self {
+        proto::Units {
+            air_pressure_at_sea_level: units.air_pressure_at_sea_level.clone(),
+            air_temperature: units.air_temperature.clone(),
+            cloud_area_fraction: units.cloud_area_fraction.clone(),
+            precipitation_amount: units.precipitation_amount.clone(),
+            relative_humidity: units.relative_humidity.clone(),
+            wind_from_direction: units.wind_from_direction.clone(),
+            wind_speed: units.wind_speed.clone(),
+        }
+    }
+}
+
+impl From<proto::Units> for Units {
+    fn from(units: proto::Units) -> Self {
+        Units {
+            air_pressure_at_sea_level: units.air_pressure_at_sea_level,
+            air_temperature: units.air_temperature,
+            cloud_area_fraction: units.cloud_area_fraction,
+            precipitation_amount: units.precipitation_amount,
+            relative_humidity: units.relative_humidity,
+            wind_from_direction: units.wind_from_direction,
+            wind_speed: units.wind_speed,
+        }
+    }
+}
+
+impl From<&Details> for proto::Details {
+    fn from(details: &Details) -> Self {
+        proto::Details {
+            air_pressure_at_sea_level: details.air_pressure_at_sea_level,
+            air_temperature: details.air_temperature,
+            cloud_area_fraction: details.cloud_area_fraction,
+            precipitation_amount: details.precipitation_amount,
+            relative_humidity: details.relative_humidity,
+            wind_from_direction: details.wind_from_direction,
+            wind_speed: details.wind_speed,
+        }
+    }
+}
+
+impl From<proto::Details> for Details {
+    fn from(details: proto::Details) -> Self {
+        Details {
+            air_pressure_at_sea_level: details.air_pressure_at_sea_level,
+            air_temperature: details.air_temperature,
+            cloud_area_fraction: details.cloud_area_fraction,
+            precipitation_amount: details.precipitation_amount,
+            relative_humidity: details.relative_humidity,
+            wind_from_direction: details.wind_from_direction,
+            wind_speed: details.wind_speed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::weather::WeatherData;
+
+    const FORECAST: &str = r#"{
+        "type": "Feature",
+        "geometry": { "type": "Point", "coordinates": [10.75, 59.91, 0] },
+        "properties": {
+            "meta": {
+                "updated_at": "2026-10-16T17:37:00Z",
+                "units": { "air_temperature": "celsius", "wind_speed": "m/s" }
+            },
+            "timeseries": [
+                {
+                    "time": "2026-10-16T18:00:00Z",
+                    "data": {
+                        "instant": { "details": { "air_temperature": 24.7, "wind_speed": 6.7 } },
+                        "next_1_hours": {
+                            "summary": { "symbol_code": "heavyrainandthunder" },
+                            "details": { "precipitation_amount": 2.4 }
+                        },
+                        "next_6_hours": {
+                            "summary": { "symbol_code": "rain" },
+                            "details": { "precipitation_amount": 0.0 }
+                        },
+                        "next_12_hours": { "summary": { "symbol_code": "cloudy" } }
+                    }
+                },
+                {
+                    "time": "2026-10-16T19:00:00Z",
+                    "data": { "instant": { "details": { "air_temperature": -3.5 } } }
+                }
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn from_protobuf_decodes_what_to_protobuf_encodes() {
+        let data: WeatherData = serde_json::from_str(FORECAST).unwrap();
+        let bytes = data.to_protobuf().unwrap();
+        let decoded = WeatherData::from_protobuf(&bytes).unwrap();
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&data).unwrap()
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use crate::weather::{DisplayOptions, WeatherData};
+
+impl WeatherData {
+    /// Roam Research block with the current conditions, tagged and linked to the location's page,
+    /// e.g. `- ☀️ Clear Sky (Day) 22.0°C #weather [[Oslo]] #forecast`
+    pub fn to_roam_block(&self, location: &str, options: &DisplayOptions) -> String {
+        let tags = format!("#weather [[{}]] #forecast", location);
+        // the block is pasted into Roam, where escape codes would show up as text
+        let options = DisplayOptions {
+            palette: None,
+            ..options.clone()
+        };
+        match self.display_minimal(&options) {
+            Ok(forecast) => format!("- {} {}\n", forecast, tags),
+            Err(_) => format!("- {}\n", tags),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::DARK;
+    use crate::weather::tests::{current_hour, forecast_from, hourly};
+
+    #[test]
+    fn block_is_uncolored_and_tagged_with_the_location() {
+        let weather_data = forecast_from(current_hour(), vec![(0, hourly("cloudy", 5.0))]);
+        let options = DisplayOptions {
+            palette: Some(DARK),
+            ..DisplayOptions::default()
+        };
+        assert_eq!(
+            weather_data.to_roam_block("Oslo", &options),
+            "- ☁️ Cloudy 5.0°C #weather [[Oslo]] #forecast\n"
+        );
+        assert_eq!(
+            forecast_from(current_hour(), Vec::new()).to_roam_block("Oslo", &options),
+            "- #weather [[Oslo]] #forecast\n"
+        );
+    }
+}
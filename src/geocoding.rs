@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+use url::Url;
 
 use crate::args::WeatherArgs;
+use crate::error::AppError;
+use crate::http::{build_client, fetch_with_retry, run_concurrently};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct GeoCodingData {
@@ -16,30 +22,123 @@ pub struct GeoCodingData {
     class: String,
     r#type: String,
     importance: f64,
+    pub address: Option<Address>,
 }
 
-pub async fn get_location_data(args: &WeatherArgs) -> Result<Option<Vec<GeoCodingData>>, reqwest::Error> {
-    let query_params = [
-        ("q", args.location.clone()),
+/// Parts of the address of a geocoding result, from Nominatim's `addressdetails`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Address {
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub country_code: Option<String>,
+    pub postcode: Option<String>,
+    pub state: Option<String>,
+}
+
+pub const GEOCODING_API_URL: &str = "https://geocode.maps.co/search";
+
+pub fn build_geocoding_url(query: &str, args: &WeatherArgs) -> Url {
+    geocoding_url(query, args, args.geocoding_api_key.as_deref())
+}
+
+/// `build_geocoding_url` for `--dry-run`, with the API key masked as `***` so that it does not
+/// end up in terminals, logs and CI output
+pub fn redacted_geocoding_url(query: &str, args: &WeatherArgs) -> Url {
+    geocoding_url(query, args, args.geocoding_api_key.as_ref().map(|_| "***"))
+}
+
+fn geocoding_url(query: &str, args: &WeatherArgs, api_key: Option<&str>) -> Url {
+    let mut query_params = vec![
+        ("q", query.to_string()),
+        ("addressdetails", String::from("1")),
         // ("limit", String::from("1")),
     ];
 
-    let geocoding_ans: Vec<GeoCodingData> = reqwest::Client::new()
-        .get(String::from("https://geocode.maps.co/search?"))
-        .query(&query_params)
-        .send()
-        .await?
-        .json()
-        .await?;
+    if let Some(country) = &args.geocoding_country {
+        query_params.push(("countrycodes", country.to_lowercase()));
+    }
+
+    if let Some(api_key) = api_key {
+        query_params.push(("api_key", api_key.to_string()));
+    }
+
+    Url::parse_with_params(GEOCODING_API_URL, &query_params).expect("geocoding url is valid")
+}
+
+pub async fn get_location_data(args: &WeatherArgs) -> Result<Option<Vec<GeoCodingData>>, reqwest::Error> {
+    get_location_data_with_client(&build_client(args)?, args.location.as_ref().unwrap(), args)
+        .await
+}
+
+pub async fn get_location_data_with_client(
+    client: &reqwest::Client,
+    query: &str,
+    args: &WeatherArgs,
+) -> Result<Option<Vec<GeoCodingData>>, reqwest::Error> {
+    let request = client.get(build_geocoding_url(query, args));
+
+    let mut geocoding_ans: Vec<GeoCodingData> = fetch_with_retry(
+        client,
+        request,
+        args.retries.unwrap(),
+        args.retry_delay_ms.unwrap(),
+    )
+    .await?
+    .error_for_status()?
+    .json()
+    .await?;
 
     // println!("{}", serde_json::to_string(&geocoding_ans).unwrap());
 
+    // the API does not always return the most relevant match first
+    geocoding_ans.sort_by(|a, b| b.importance.total_cmp(&a.importance));
+
     match geocoding_ans.len() {
         0 => Ok(None),
         _ => Ok(Some(geocoding_ans)),
     }
 }
 
+/// Geocodes every query with at most `concurrency` requests at the same time, keeping the order
+/// of `queries`
+///
+/// `indices` gives, for each query, which of the results sorted by importance is used, as given
+/// by `WeatherArgs::geocoding_index`. `args` gives the request settings of
+/// `get_location_data_with_client`.
+pub async fn geocode_batch(
+    queries: Vec<String>,
+    client: &reqwest::Client,
+    concurrency: usize,
+    indices: Vec<usize>,
+    args: Arc<WeatherArgs>,
+) -> Vec<Result<GeoCodingData, AppError>> {
+    let queries = queries.into_iter().zip(indices).collect();
+
+    run_concurrently(queries, concurrency, |(query, index)| {
+        let client = client.clone();
+        let args = Arc::clone(&args);
+        async move {
+            get_location_data_with_client(&client, &query, &args)
+                .await
+                .map_err(|e| AppError::network(e, &args))
+                .and_then(|results| select_result(results, index, &query))
+        }
+    })
+    .await
+}
+
+/// The `index`-th of the geocoding `results` for `query`, which is not found when there are
+/// fewer
+pub(crate) fn select_result(
+    results: Option<Vec<GeoCodingData>>,
+    index: usize,
+    query: &str,
+) -> Result<GeoCodingData, AppError> {
+    results
+        .and_then(|results| results.into_iter().nth(index))
+        .ok_or_else(|| AppError::LocationNotFound(query.to_string()))
+}
+
 pub fn get_cooordinates(data: &GeoCodingData) -> (f64, f64) {
     (data.lat.parse::<f64>().unwrap(), data.lon.parse::<f64>().unwrap(), )
 }
@@ -48,5 +147,167 @@ pub fn get_display_name(data: &GeoCodingData) -> String {
     data.display_name.clone()
 }
 
+pub fn get_city(data: &GeoCodingData) -> Option<String> {
+    data.address.as_ref()?.city.clone()
+}
+
+/// A location given directly as `lat,lon`, which needs no geocoding
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Debug)]
+pub enum CoordinatesError {
+    InvalidFormat(String),
+    LatitudeOutOfRange(f64),
+    LongitudeOutOfRange(f64),
+}
+
+impl Display for CoordinatesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoordinatesError::InvalidFormat(s) => {
+                write!(f, "'{}' is not in the form 'lat,lon'", s)
+            }
+            CoordinatesError::LatitudeOutOfRange(lat) => {
+                write!(f, "latitude {} is outside -90..=90", lat)
+            }
+            CoordinatesError::LongitudeOutOfRange(lon) => {
+                write!(f, "longitude {} is outside -180..=180", lon)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CoordinatesError {}
+
+impl FromStr for Coordinates {
+    type Err = CoordinatesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || CoordinatesError::InvalidFormat(s.to_string());
+        // `f64` also parses "NaN" and "inf", which are no place on the map
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .ok_or_else(invalid)
+        };
+
+        let (lat, lon) = s.split_once(',').ok_or_else(invalid)?;
+        let lat = parse(lat)?;
+        let lon = parse(lon)?;
+
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(CoordinatesError::LatitudeOutOfRange(lat));
+        }
+        if !(-180.0..=180.0).contains(&lon) {
+            return Err(CoordinatesError::LongitudeOutOfRange(lon));
+        }
+
+        Ok(Coordinates { lat, lon })
+    }
+}
+
+impl Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.lat, self.lon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Geocoding result for `name`, as the API returns it
+    fn result(name: &str) -> GeoCodingData {
+        serde_json::from_value(serde_json::json!({
+            "place_id": 1,
+            "licence": "Data © OpenStreetMap contributors, ODbL 1.0",
+            "powered_by": "Map Maker: https://maps.co",
+            "osm_type": "relation",
+            "osm_id": 1,
+            "boundingbox": ["51.3", "51.7", "-0.5", "0.3"],
+            "lat": "51.5",
+            "lon": "-0.1",
+            "display_name": name,
+            "class": "boundary",
+            "type": "administrative",
+            "importance": 0.5,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn the_geocoding_index_selects_that_result() {
+        let results = || Some(vec![result("London, UK"), result("London, Ontario, Canada")]);
+
+        let best = select_result(results(), 0, "London").unwrap();
+        assert_eq!(get_display_name(&best), "London, UK");
+        let second = select_result(results(), 1, "London").unwrap();
+        assert_eq!(get_display_name(&second), "London, Ontario, Canada");
+        assert!(matches!(
+            select_result(results(), 2, "London"),
+            Err(AppError::LocationNotFound(query)) if query == "London"
+        ));
+        assert!(select_result(None, 0, "London").is_err());
+    }
 
+    #[test]
+    fn dry_run_masks_the_api_key() {
+        let args = WeatherArgs::builder()
+            .location("Oslo")
+            .geocoding_api_key("SECRET")
+            .build()
+            .unwrap();
+        assert!(build_geocoding_url("Oslo", &args)
+            .as_str()
+            .ends_with("&api_key=SECRET"));
 
+        let url = redacted_geocoding_url("Oslo", &args).to_string();
+        assert!(!url.contains("SECRET"), "{}", url);
+        assert!(url.ends_with("&api_key=***"), "{}", url);
+
+        let args = WeatherArgs {
+            geocoding_api_key: None,
+            ..args
+        };
+        assert!(!redacted_geocoding_url("Oslo", &args)
+            .as_str()
+            .contains("api_key"));
+    }
+
+    #[test]
+    fn geocoding_indices_count_from_one_per_batch_line() {
+        let args = WeatherArgs::builder().location("London").build().unwrap();
+        assert_eq!(args.geocoding_index(0), 0);
+
+        let args = WeatherArgs {
+            geocoding_index: vec![2, 1, 3],
+            batch_file: Some("locations.txt".into()),
+            ..args
+        };
+        assert!(args.validate().is_ok());
+        assert_eq!(args.geocoding_index(0), 1);
+        assert_eq!(args.geocoding_index(2), 2);
+        assert_eq!(args.geocoding_index(3), 0);
+
+        let args = WeatherArgs {
+            geocoding_index: vec![0, 11],
+            batch_file: None,
+            ..args
+        };
+        assert_eq!(
+            args.validate().unwrap_err(),
+            [
+                "--geocoding-index must be between 1 and 10, got 0",
+                "--geocoding-index must be between 1 and 10, got 11",
+                "--geocoding-index takes a single index unless --batch-file is given",
+            ]
+        );
+    }
+}
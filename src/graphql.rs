@@ -0,0 +1,189 @@
+//! `GET /graphql` of `weather-cli serve`: the forecast of a location over a duration, with the
+//! fields a client asks for
+
+use std::sync::Arc;
+
+use async_graphql::{http, Context, EmptyMutation, EmptySubscription, Object, SimpleObject};
+use axum::extract::{RawQuery, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+
+use crate::args::DurationType;
+use crate::serve::{error_response, Server};
+use crate::weather::{self, Timeseries, WeatherData};
+
+type Schema = async_graphql::Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Forecast of a location
+#[derive(SimpleObject)]
+struct Weather {
+    /// The location as given in the query
+    location: String,
+    latitude: f64,
+    longitude: f64,
+    /// Time at which met.no last updated the forecast
+    updated_at: DateTime<Utc>,
+    units: Units,
+    forecast: Vec<Forecast>,
+}
+
+/// Units of the values of the forecast, as given by met.no
+#[derive(SimpleObject)]
+struct Units {
+    air_pressure_at_sea_level: Option<String>,
+    air_temperature: Option<String>,
+    cloud_area_fraction: Option<String>,
+    precipitation_amount: Option<String>,
+    relative_humidity: Option<String>,
+    wind_from_direction: Option<String>,
+    wind_speed: Option<String>,
+}
+
+/// Forecast of one period of the duration
+#[derive(SimpleObject)]
+struct Forecast {
+    /// Start of the period
+    time: DateTime<Utc>,
+    /// met.no symbol code of the period, e.g. `partlycloudy_day`
+    symbol_code: Option<String>,
+    temperature: Option<f64>,
+    wind_speed: Option<f64>,
+    wind_from_direction: Option<f64>,
+    relative_humidity: Option<f64>,
+    cloud_area_fraction: Option<f64>,
+    air_pressure_at_sea_level: Option<f64>,
+    /// Precipitation over the period
+    precipitation_amount: Option<f64>,
+}
+
+impl From<&weather::Units> for Units {
+    fn from(units: &weather::Units) -> Self {
+        Units {
+            air_pressure_at_sea_level: units.air_pressure_at_sea_level.clone(),
+            air_temperature: units.air_temperature.clone(),
+            cloud_area_fraction: units.cloud_area_fraction.clone(),
+            precipitation_amount: units.precipitation_amount.clone(),
+            relative_humidity: units.relative_humidity.clone(),
+            wind_from_direction: units.wind_from_direction.clone(),
+            wind_speed: units.wind_speed.clone(),
+        }
+    }
+}
+
+impl Forecast {
+    fn new(timeseries: &Timeseries, duration: DurationType) -> Forecast {
+        let details = timeseries.details();
+        Forecast {
+            time: timeseries.time,
+            symbol_code: timeseries
+                .summary(duration)
+                .ok()
+                .map(|summary| summary.symbol_code.clone()),
+            temperature: details.air_temperature,
+            wind_speed: details.wind_speed,
+            wind_from_direction: details.wind_from_direction,
+            relative_humidity: details.relative_humidity,
+            cloud_area_fraction: details.cloud_area_fraction,
+            air_pressure_at_sea_level: details.air_pressure_at_sea_level,
+            precipitation_amount: timeseries.precipitation_amount(duration),
+        }
+    }
+}
+
+impl Weather {
+    /// The entries of `weather_data` covered by `duration` from `now`
+    fn new(
+        location: String,
+        weather_data: &WeatherData,
+        duration: DurationType,
+        now: DateTime<Utc>,
+    ) -> Weather {
+        let coordinates = &weather_data.geometry.coordinates;
+        Weather {
+            location,
+            latitude: coordinates.get(1).copied().unwrap_or_default(),
+            longitude: coordinates.first().copied().unwrap_or_default(),
+            updated_at: weather_data.properties.meta.updated_at,
+            units: Units::from(&weather_data.properties.meta.units),
+            forecast: weather_data
+                .lazy_timeseries(duration, now, None)
+                .map(|timeseries| Forecast::new(timeseries, duration))
+                .collect(),
+        }
+    }
+}
+
+pub(crate) struct Query;
+
+#[Object]
+impl Query {
+    /// Forecast for a place name, `lat,lon` or saved alias over a `--duration` of the CLI,
+    /// e.g. `weather(location: "Oslo", duration: "today")`
+    async fn weather(
+        &self,
+        ctx: &Context<'_>,
+        location: String,
+        #[graphql(default = "now")] duration: String,
+    ) -> async_graphql::Result<Weather> {
+        let duration = DurationType::from_str(&duration, true)
+            .map_err(|_| format!("unknown duration '{}'", duration))?;
+        let server = ctx.data::<Arc<Server>>()?;
+        let weather_data = server.forecast(&location).await?;
+        let now = server.display_options().now();
+        Ok(Weather::new(location, &weather_data, duration, now))
+    }
+}
+
+impl WeatherData {
+    /// GraphQL SDL of `GET /graphql`, generated from the types of the schema
+    pub fn graphql_schema() -> String {
+        Schema::build(Query, EmptyMutation, EmptySubscription)
+            .finish()
+            .sdl()
+    }
+}
+
+/// Router answering `GET /graphql?query=...`, with the `variables` and `operationName`
+/// parameters of GraphQL over HTTP
+pub(crate) fn router(server: Arc<Server>) -> Router {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(server)
+        .finish();
+    Router::new()
+        .route("/graphql", get(get_graphql))
+        .with_state(schema)
+}
+
+async fn get_graphql(State(schema): State<Schema>, RawQuery(query): RawQuery) -> Response {
+    match http::parse_query_string(query.as_deref().unwrap_or_default()) {
+        Ok(request) => Json(schema.execute(request).await).into_response(),
+        Err(e) => error_response(StatusCode::BAD_REQUEST, &e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_has_the_weather_query() {
+        let sdl = WeatherData::graphql_schema();
+        assert!(sdl.contains("weather(location: String!, duration: String! = \"now\"): Weather!"));
+        for field in ["temperature: Float", "windSpeed: Float", "updatedAt: DateTime!"] {
+            assert!(sdl.contains(field), "no {} in\n{}", field, sdl);
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_durations_are_errors() {
+        let schema = Schema::build(Query, EmptyMutation, EmptySubscription).finish();
+        let response = schema
+            .execute(r#"{ weather(location: "Oslo", duration: "fortnight") { location } }"#)
+            .await;
+        assert_eq!(response.errors[0].message, "unknown duration 'fortnight'");
+    }
+}
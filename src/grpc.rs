@@ -0,0 +1,63 @@
+//! `weather.Weather` gRPC service of `weather.proto`, on the server side that build.rs generates
+//! with tonic-build
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use crate::args::WeatherArgs;
+use crate::error::AppError;
+use crate::export::proto::weather_server::{Weather, WeatherServer};
+use crate::export::proto;
+use crate::serve::Server;
+
+/// Server side of the `weather.Weather` service, to add to a `tonic` server wrapped in a
+/// `WeatherServer`
+#[derive(Clone)]
+pub struct WeatherService {
+    server: Arc<Server>,
+}
+
+impl WeatherService {
+    pub fn new(args: WeatherArgs) -> Result<WeatherService, AppError> {
+        Ok(WeatherService {
+            server: Arc::new(Server::new(args)?),
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl Weather for WeatherService {
+    async fn get_forecast(
+        &self,
+        request: Request<proto::ForecastRequest>,
+    ) -> Result<Response<proto::WeatherData>, Status> {
+        let location = request.into_inner().location;
+        if location.is_empty() {
+            return Err(Status::invalid_argument("missing the location"));
+        }
+        let weather_data = self.server.forecast(&location).await.map_err(status)?;
+        Ok(Response::new(proto::WeatherData::from(&weather_data)))
+    }
+}
+
+/// Status of the error `server.forecast` failed with
+fn status(e: AppError) -> Status {
+    match e {
+        AppError::Coordinates(_) => Status::invalid_argument(e.to_string()),
+        AppError::LocationNotFound(_) => Status::not_found(e.to_string()),
+        AppError::Network(_) | AppError::Weather(_) => Status::unavailable(e.to_string()),
+        _ => Status::internal(e.to_string()),
+    }
+}
+
+/// Answers gRPC requests on `address` until the process is stopped
+pub(crate) async fn serve_grpc(server: Arc<Server>, address: SocketAddr) -> Result<(), AppError> {
+    eprintln!("Serving gRPC on {}", address);
+    tonic::transport::Server::builder()
+        .add_service(WeatherServer::new(WeatherService { server }))
+        .serve(address)
+        .await
+        .map_err(|e| AppError::Io(std::io::Error::other(e)))
+}
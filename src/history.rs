@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::path::PathBuf;
+
+use crate::locations::SavedLocation;
+
+/// Number of searches kept in the history file
+const MAX_ENTRIES: usize = 100;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HistoryEntry {
+    pub timestamp: DateTime<Utc>,
+    pub query: String,
+    pub resolved_display_name: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl Display for HistoryEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}  {} -> {} ({}, {})",
+            self.timestamp.format("%Y-%m-%d %H:%M"),
+            self.query,
+            self.resolved_display_name,
+            self.lat,
+            self.lon
+        )
+    }
+}
+
+/// Searched locations stored in `~/.local/share/weather-cli/history.json`, oldest first
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct LocationHistory {
+    pub entries: Vec<HistoryEntry>,
+}
+
+#[derive(Debug)]
+pub enum HistoryError {
+    MissingDataDir,
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::MissingDataDir => write!(f, "Could not determine the data directory"),
+            HistoryError::Io(e) => write!(f, "Could not access the location history: {}", e),
+            HistoryError::Json(e) => write!(f, "Could not read the location history: {}", e),
+        }
+    }
+}
+
+fn history_path() -> Result<PathBuf, HistoryError> {
+    dirs::data_dir()
+        .map(|dir| dir.join("weather-cli").join("history.json"))
+        .ok_or(HistoryError::MissingDataDir)
+}
+
+impl LocationHistory {
+    pub fn load() -> Result<LocationHistory, HistoryError> {
+        let path = history_path()?;
+        if !path.exists() {
+            return Ok(LocationHistory::default());
+        }
+
+        let contents = std::fs::read_to_string(path).map_err(HistoryError::Io)?;
+        serde_json::from_str(&contents).map_err(HistoryError::Json)
+    }
+
+    pub fn save(&self) -> Result<(), HistoryError> {
+        let path = history_path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(HistoryError::Io)?;
+        }
+
+        let contents = serde_json::to_string_pretty(self).map_err(HistoryError::Json)?;
+        std::fs::write(path, contents).map_err(HistoryError::Io)
+    }
+
+    /// Deletes the history file
+    pub fn clear() -> Result<(), HistoryError> {
+        let path = history_path()?;
+        if !path.exists() {
+            return Ok(());
+        }
+        std::fs::remove_file(path).map_err(HistoryError::Io)
+    }
+
+    /// Appends a search for `query`, dropping the oldest entries beyond `MAX_ENTRIES`
+    pub fn record(&mut self, query: &str, location: &SavedLocation) {
+        self.entries.push(HistoryEntry {
+            timestamp: Utc::now(),
+            query: query.to_string(),
+            resolved_display_name: location.display_name.clone(),
+            lat: location.lat,
+            lon: location.lon,
+        });
+        let excess = self.entries.len().saturating_sub(MAX_ENTRIES);
+        self.entries.drain(..excess);
+    }
+
+    /// The `count` most recent entries, newest first
+    pub fn latest(&self, count: usize) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter().rev().take(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(name: &str) -> SavedLocation {
+        SavedLocation {
+            lat: 59.91,
+            lon: 10.75,
+            display_name: name.to_string(),
+            city: None,
+        }
+    }
+
+    #[test]
+    fn record_keeps_the_latest_entries() {
+        let mut history = LocationHistory::default();
+        for search in 0..MAX_ENTRIES + 5 {
+            history.record(&search.to_string(), &location("Oslo, Norway"));
+        }
+        assert_eq!(history.entries.len(), MAX_ENTRIES);
+        assert_eq!(history.entries[0].query, "5");
+
+        let latest: Vec<&str> = history
+            .latest(2)
+            .map(|entry| entry.query.as_str())
+            .collect();
+        assert_eq!(latest, ["104", "103"]);
+        assert_eq!(history.latest(MAX_ENTRIES + 1).count(), MAX_ENTRIES);
+    }
+
+    #[test]
+    fn entries_show_the_query_and_where_it_led() {
+        let mut history = LocationHistory::default();
+        history.record("oslo", &location("Oslo, Norway"));
+        let entry = history.entries[0].to_string();
+        assert!(
+            entry.ends_with("  oslo -> Oslo, Norway (59.91, 10.75)"),
+            "{}",
+            entry
+        );
+    }
+}
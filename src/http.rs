@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::RETRY_AFTER;
+use reqwest::StatusCode;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::args::WeatherArgs;
+
+const RETRYABLE_STATUSES: [StatusCode; 5] = [
+    StatusCode::TOO_MANY_REQUESTS,
+    StatusCode::INTERNAL_SERVER_ERROR,
+    StatusCode::BAD_GATEWAY,
+    StatusCode::SERVICE_UNAVAILABLE,
+    StatusCode::GATEWAY_TIMEOUT,
+];
+
+/// met.no rejects requests without an identifying User-Agent
+pub const DEFAULT_USER_AGENT: &str = concat!("Weather-Cli/", env!("CARGO_PKG_VERSION"));
+
+pub fn build_client(args: &WeatherArgs) -> Result<reqwest::Client, reqwest::Error> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_secs(args.timeout.unwrap()))
+        .user_agent(args.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT));
+
+    // without --proxy, reqwest already picks up HTTP_PROXY / HTTPS_PROXY from the environment
+    if let Some(proxy) = &args.proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+
+    builder.build()
+}
+
+pub async fn fetch_with_retry(
+    client: &reqwest::Client,
+    request: reqwest::RequestBuilder,
+    max_retries: u32,
+    base_delay_ms: u64,
+) -> Result<reqwest::Response, reqwest::Error> {
+    let request = request.build()?;
+    let mut attempt = 0;
+
+    loop {
+        // requests built by this crate never have streaming bodies, so they can always be cloned
+        let response = client
+            .execute(
+                request
+                    .try_clone()
+                    .expect("request body should be clonable"),
+            )
+            .await;
+
+        let retryable = match &response {
+            Ok(response) => RETRYABLE_STATUSES.contains(&response.status()),
+            Err(e) => e.is_timeout() || e.is_connect(),
+        };
+
+        if !retryable || attempt >= max_retries {
+            return response;
+        }
+
+        let retry_after = response.as_ref().ok().and_then(retry_after);
+        let Some(delay) = retry_delay(retry_after, attempt, max_retries, base_delay_ms) else {
+            return response;
+        };
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Delay before retrying after `attempt`, `None` when the server asks for a longer wait than
+/// the backoff of the last retry, which is not worth blocking on
+fn retry_delay(
+    retry_after: Option<Duration>,
+    attempt: u32,
+    max_retries: u32,
+    base_delay_ms: u64,
+) -> Option<Duration> {
+    // a server that says when to come back knows better than our backoff
+    match retry_after {
+        Some(delay) if delay > max_backoff_delay(max_retries, base_delay_ms) => None,
+        Some(delay) => Some(delay),
+        None => Some(backoff_delay(attempt, base_delay_ms)),
+    }
+}
+
+/// Longest delay `backoff_delay` can take before the last of `max_retries` retries
+fn max_backoff_delay(max_retries: u32, base_delay_ms: u64) -> Duration {
+    let delay = base_delay_ms.saturating_mul(1 << max_retries.saturating_sub(1).min(16));
+    Duration::from_millis(delay.saturating_add(delay / 2))
+}
+
+fn backoff_delay(attempt: u32, base_delay_ms: u64) -> Duration {
+    // double the delay on every attempt and add up to 50% of random jitter
+    let delay = base_delay_ms.saturating_mul(1 << attempt.min(16));
+    let jitter = rand::thread_rng().gen_range(0..=delay / 2);
+    Duration::from_millis(delay.saturating_add(jitter))
+}
+
+/// Delay requested by a `Retry-After` header, given either in seconds or as an HTTP date
+pub fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    // a date in the past means the request can be retried right away
+    Some((date.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or_default())
+}
+
+/// Runs `task` on every item with at most `concurrency` of them in flight, returning the outputs
+/// in the order of `items`
+pub(crate) async fn run_concurrently<I, T, F, Fut>(
+    items: Vec<I>,
+    concurrency: usize,
+    task: F,
+) -> Vec<T>
+where
+    F: Fn(I) -> Fut,
+    Fut: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+
+    let mut tasks = JoinSet::new();
+    for (position, item) in items.into_iter().enumerate() {
+        let semaphore = Arc::clone(&semaphore);
+        // futures do nothing until polled, so the request only starts once a permit is free
+        let future = task(item);
+        tasks.spawn(async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("semaphore is never closed");
+            (position, future.await)
+        });
+    }
+
+    let mut outputs = Vec::new();
+    while let Some(output) = tasks.join_next().await {
+        outputs.push(output.expect("concurrent task panicked"));
+    }
+    outputs.sort_by_key(|(position, _)| *position);
+    outputs.into_iter().map(|(_, output)| output).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_after_is_capped_at_the_longest_backoff() {
+        // 500 ms doubled twice before the third retry, with up to 50% of jitter
+        assert_eq!(max_backoff_delay(3, 500), Duration::from_millis(3000));
+
+        let wait = |seconds| retry_delay(Some(Duration::from_secs(seconds)), 0, 3, 500);
+        assert_eq!(wait(2), Some(Duration::from_secs(2)));
+        assert_eq!(wait(3), Some(Duration::from_secs(3)));
+        assert_eq!(wait(4), None);
+        assert_eq!(wait(3600), None);
+
+        let backoff = retry_delay(None, 1, 3, 500).unwrap();
+        assert!((Duration::from_millis(1000)..=Duration::from_millis(1500)).contains(&backoff));
+    }
+
+    #[tokio::test]
+    async fn concurrent_outputs_keep_the_order_of_the_items() {
+        // the first item finishes last
+        let outputs = run_concurrently(vec![30, 20, 10, 0], 4, |millis| async move {
+            tokio::time::sleep(Duration::from_millis(millis)).await;
+            millis
+        })
+        .await;
+        assert_eq!(outputs, [30, 20, 10, 0]);
+    }
+}
@@ -0,0 +1,23 @@
+pub mod args;
+pub mod batch;
+pub mod cache;
+pub mod charts;
+pub mod color;
+pub mod comparison;
+pub mod condition;
+pub mod error;
+pub mod export;
+pub mod geocoding;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod history;
+pub mod http;
+pub mod locations;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod table;
+pub mod weather;
+#[cfg(feature = "websocket")]
+pub mod websocket;
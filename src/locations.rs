@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::path::PathBuf;
+
+use crate::args::{LocationsAction, OutputMode, WeatherArgs};
+use crate::error::AppError;
+use crate::geocoding::{
+    get_city, get_cooordinates, get_display_name, get_location_data_with_client, select_result,
+    Coordinates, CoordinatesError, GeoCodingData,
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavedLocation {
+    pub lat: f64,
+    pub lon: f64,
+    pub display_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+}
+
+impl From<&GeoCodingData> for SavedLocation {
+    fn from(data: &GeoCodingData) -> Self {
+        let (lat, lon) = get_cooordinates(data);
+        SavedLocation {
+            lat,
+            lon,
+            display_name: get_display_name(data),
+            city: get_city(data),
+        }
+    }
+}
+
+impl From<Coordinates> for SavedLocation {
+    fn from(coords: Coordinates) -> Self {
+        SavedLocation {
+            lat: coords.lat,
+            lon: coords.lon,
+            display_name: coords.to_string(),
+            city: None,
+        }
+    }
+}
+
+impl SavedLocation {
+    /// Name shown in the forecast header: just the city in compact output, when it is known
+    pub fn display_name_for(&self, output_mode: OutputMode) -> String {
+        match (output_mode, &self.city) {
+            (OutputMode::Compact, Some(city)) => city.clone(),
+            _ => self.display_name.clone(),
+        }
+    }
+}
+
+impl Display for SavedLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}, {})", self.display_name, self.lat, self.lon)
+    }
+}
+
+/// Location aliases stored in `~/.config/weather-cli/locations.toml`
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct SavedLocations {
+    locations: BTreeMap<String, SavedLocation>,
+}
+
+#[derive(Debug)]
+pub enum LocationsError {
+    MissingConfigDir,
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+    UnknownAlias(String),
+}
+
+impl Display for LocationsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocationsError::MissingConfigDir => {
+                write!(f, "Could not determine the config directory")
+            }
+            LocationsError::Io(e) => write!(f, "Could not access saved locations: {}", e),
+            LocationsError::Parse(e) => write!(f, "Could not parse saved locations: {}", e),
+            LocationsError::Serialize(e) => write!(f, "Could not serialize saved locations: {}", e),
+            LocationsError::UnknownAlias(alias) => {
+                write!(f, "No saved location named \"{}\"", alias)
+            }
+        }
+    }
+}
+
+fn locations_path() -> Result<PathBuf, LocationsError> {
+    dirs::config_dir()
+        .map(|dir| dir.join("weather-cli").join("locations.toml"))
+        .ok_or(LocationsError::MissingConfigDir)
+}
+
+impl SavedLocations {
+    pub fn load() -> Result<SavedLocations, LocationsError> {
+        let path = locations_path()?;
+        if !path.exists() {
+            return Ok(SavedLocations::default());
+        }
+
+        let contents = std::fs::read_to_string(path).map_err(LocationsError::Io)?;
+        toml::from_str(&contents).map_err(LocationsError::Parse)
+    }
+
+    pub fn save(&self) -> Result<(), LocationsError> {
+        let path = locations_path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(LocationsError::Io)?;
+        }
+
+        let contents = toml::to_string(self).map_err(LocationsError::Serialize)?;
+        std::fs::write(path, contents).map_err(LocationsError::Io)
+    }
+
+    pub fn get(&self, alias: &str) -> Option<&SavedLocation> {
+        self.locations.get(alias)
+    }
+
+    pub fn insert(&mut self, alias: String, location: SavedLocation) {
+        self.locations.insert(alias, location);
+    }
+
+    pub fn remove(&mut self, alias: &str) -> Result<SavedLocation, LocationsError> {
+        self.locations
+            .remove(alias)
+            .ok_or_else(|| LocationsError::UnknownAlias(alias.to_string()))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &SavedLocation)> {
+        self.locations.iter()
+    }
+}
+
+/// `query` as coordinates or a saved alias, `None` when it has to be geocoded
+pub fn resolve_without_geocoding(
+    query: &str,
+    saved_locations: &SavedLocations,
+) -> Option<Result<SavedLocation, AppError>> {
+    match query.parse::<Coordinates>() {
+        Ok(coords) => Some(Ok(coords.into())),
+        // anything that is not a coordinate pair is a place name for the geocoder
+        Err(CoordinatesError::InvalidFormat(_)) => saved_locations.get(query).cloned().map(Ok),
+        Err(e) => Some(Err(e.into())),
+    }
+}
+
+/// Looks `query` up in the saved aliases first and only geocodes it when there is no match
+pub async fn resolve_location(
+    client: &reqwest::Client,
+    query: &str,
+    saved_locations: &SavedLocations,
+    args: &WeatherArgs,
+) -> Result<SavedLocation, AppError> {
+    if let Some(location) = resolve_without_geocoding(query, saved_locations) {
+        return location;
+    }
+
+    let results = get_location_data_with_client(client, query, args)
+        .await
+        .map_err(|e| AppError::network(e, args))?;
+    select_result(results, args.geocoding_index(0), query).map(|data| SavedLocation::from(&data))
+}
+
+pub fn manage_locations(action: &LocationsAction) -> Result<(), LocationsError> {
+    let mut saved_locations = SavedLocations::load()?;
+
+    match action {
+        LocationsAction::List => {
+            for (alias, location) in saved_locations.iter() {
+                println!("{}: {}", alias, location);
+            }
+        }
+        LocationsAction::Remove { alias } => {
+            saved_locations.remove(alias)?;
+            saved_locations.save()?;
+        }
+        LocationsAction::Show { alias } => {
+            let location = saved_locations
+                .get(alias)
+                .ok_or_else(|| LocationsError::UnknownAlias(alias.clone()))?;
+            println!("{}", location);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oslo() -> SavedLocation {
+        SavedLocation {
+            lat: 59.91,
+            lon: 10.75,
+            display_name: "Oslo, Norway".to_string(),
+            city: Some("Oslo".to_string()),
+        }
+    }
+
+    #[test]
+    fn removing_an_unknown_alias_fails() {
+        let mut saved_locations = SavedLocations::default();
+        saved_locations.insert("home".to_string(), oslo());
+        match saved_locations.remove("work") {
+            Err(e) => assert_eq!(e.to_string(), "No saved location named \"work\""),
+            Ok(_) => panic!("\"work\" is not saved"),
+        }
+        assert_eq!(
+            saved_locations.remove("home").unwrap().display_name,
+            "Oslo, Norway"
+        );
+        assert!(saved_locations.get("home").is_none());
+    }
+
+    #[test]
+    fn compact_output_names_just_the_city() {
+        assert_eq!(oslo().display_name_for(OutputMode::Compact), "Oslo");
+        assert_eq!(
+            oslo().display_name_for(OutputMode::Detailed),
+            "Oslo, Norway"
+        );
+        let coordinates = SavedLocation::from("59.91,10.75".parse::<Coordinates>().unwrap());
+        assert_eq!(
+            coordinates.display_name_for(OutputMode::Compact),
+            "59.91,10.75"
+        );
+    }
+
+    #[test]
+    fn coordinates_and_aliases_resolve_without_geocoding() {
+        let mut saved_locations = SavedLocations::default();
+        saved_locations.insert("home".to_string(), oslo());
+
+        let location = resolve_without_geocoding("60.39, 5.32", &saved_locations);
+        assert!(matches!(
+            location,
+            Some(Ok(SavedLocation {
+                lat: 60.39,
+                lon: 5.32,
+                ..
+            }))
+        ));
+        let location = resolve_without_geocoding("home", &saved_locations);
+        assert!(matches!(location, Some(Ok(location)) if location.display_name == "Oslo, Norway"));
+        assert!(resolve_without_geocoding("Bergen", &saved_locations).is_none());
+        assert!(matches!(
+            resolve_without_geocoding("91,0", &saved_locations),
+            Some(Err(_))
+        ));
+    }
+}
@@ -1,28 +1,239 @@
-use clap::Parser;
-use geocoding::{get_cooordinates, get_display_name};
+use clap::{CommandFactory, Parser};
+use std::process::{self, Child, Stdio};
+use std::sync::Arc;
+use weather_cli::args::{self, Command, DurationType, WeatherArgs};
+use weather_cli::error::AppError;
+use weather_cli::geocoding::Coordinates;
+use weather_cli::history::{HistoryError, LocationHistory};
+use weather_cli::http::build_client;
+use weather_cli::locations::{self, SavedLocation, SavedLocations};
+use weather_cli::weather::{DisplayOptions, WeatherData, WeatherError};
+use weather_cli::{batch, cache, geocoding, weather};
 
-pub mod args;
-pub mod geocoding;
-pub mod weather;
+/// Number of entries printed by `--history`
+const HISTORY_LENGTH: usize = 10;
 
 #[tokio::main]
-async fn main() -> Result<(), reqwest::Error> {
+async fn main() {
     let args = args::WeatherArgs::parse();
 
-    let coords = &(geocoding::get_location_data(&args).await?.unwrap()[0]);
+    if let Err(errors) = args.validate() {
+        for error in errors {
+            eprintln!("error: {}", error);
+        }
+        // the exit code of clap's own usage errors
+        process::exit(2);
+    }
 
-    let weather_data = weather::get_weather_data(get_cooordinates(&coords))
-        .await?
-        .unwrap();
+    if let Err(e) = run(args).await {
+        eprintln!("{}", e);
+        process::exit(e.exit_code());
+    }
+}
+
+async fn run(args: WeatherArgs) -> Result<(), AppError> {
+    if let Some(Command::Locations { action }) = &args.command {
+        return Ok(locations::manage_locations(action)?);
+    }
+
+    #[cfg(feature = "serve")]
+    if let Some(Command::Serve(serve_args)) = &args.command {
+        let serve_args = serve_args.clone();
+        return weather_cli::serve::serve(args, serve_args).await;
+    }
+
+    if let Some(shell) = args.generate_completion {
+        let mut command = WeatherArgs::command();
+        let name = command.get_name().to_string();
+        clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+        return Ok(());
+    }
+
+    if args.print_schema {
+        println!("{}", WeatherData::json_schema());
+        return Ok(());
+    }
+
+    if args.clear_history {
+        LocationHistory::clear()?;
+        return Ok(());
+    }
+
+    if args.history {
+        for entry in LocationHistory::load()?.latest(HISTORY_LENGTH) {
+            println!("{}", entry);
+        }
+        return Ok(());
+    }
+
+    let mut saved_locations = SavedLocations::load()?;
 
-    let _ = match weather_data.display(
-        args.duration.unwrap(),
-        get_display_name(&coords),
-        args.output_mode.unwrap(),
-    ) {
-        Ok(_) => (),
-        Err(e) => println!("{}", e),
+    if args.dry_run {
+        match &args.batch_file {
+            Some(path) => {
+                for query in batch::read_batch_queries(path)? {
+                    print_dry_run(&query, &args, &saved_locations);
+                }
+            }
+            None => print_dry_run(args.location.as_ref().unwrap(), &args, &saved_locations),
+        }
+        return Ok(());
+    }
+
+    if let Some(path) = args.batch_file.clone() {
+        let args = Arc::new(args);
+        let results = batch::fetch_batch(&path, saved_locations, Arc::clone(&args)).await?;
+        print!("{}", batch::format_batch_csv(&results, &args));
+        return Ok(());
+    }
+
+    let client = build_client(&args).map_err(|e| AppError::network(e, &args))?;
+    let location = locations::resolve_location(
+        &client,
+        args.location.as_ref().unwrap(),
+        &saved_locations,
+        &args,
+    )
+    .await?;
+
+    // the forecast does not need the history, so a broken history file is only worth a warning
+    if let Err(e) = record_history(args.location.as_ref().unwrap(), &location) {
+        eprintln!("warning: {}", e);
+    }
+
+    if let Some(alias) = &args.save_location {
+        saved_locations.insert(alias.clone(), location.clone());
+        saved_locations.save()?;
+    }
+
+    let weather_data = cache::get_weather_data_cached(&client, (location.lat, location.lon), &args)
+        .await
+        .map_err(|e| AppError::weather(e, &args))?
+        .ok_or(AppError::Weather(WeatherError::MissingData))?;
+
+    let fresh = match args.max_age() {
+        Some(max_age) => weather_data.check_age(max_age),
+        None => Ok(()),
     };
 
+    if let DurationType::Custom { start, end } = args.duration() {
+        weather_data.check_date_range(start, end)?;
+    }
+
+    if args.since_update {
+        println!("Updated {}", weather::format_age(weather_data.age()));
+        return Ok(fresh?);
+    }
+    fresh?;
+
+    let pager = match args.pager && !args.no_pager {
+        true => spawn_pager(),
+        false => None,
+    };
+    if let Some(mut pager) = pager {
+        let mut stdin = pager.stdin.take().unwrap();
+        let displayed = weather_data.display_to(
+            args.duration(),
+            location.display_name_for(args.output_mode.unwrap()),
+            args.output_mode.unwrap(),
+            &DisplayOptions::from(&args),
+            &mut stdin,
+        );
+        // closing stdin lets the pager know the output is complete
+        drop(stdin);
+        pager.wait()?;
+        displayed?;
+    } else {
+        weather_data.display(
+            args.duration(),
+            location.display_name_for(args.output_mode.unwrap()),
+            args.output_mode.unwrap(),
+            &DisplayOptions::from(&args),
+        )?;
+    }
+
+    #[cfg(feature = "mqtt")]
+    if let Some(broker) = &args.mqtt_broker {
+        let topic_prefix = format!("weather/{}", args.location.as_ref().unwrap());
+        let timeout = std::time::Duration::from_secs(args.timeout.unwrap());
+        weather_data
+            .publish_mqtt(broker, &topic_prefix, timeout)
+            .await?;
+    }
+
+    let options = DisplayOptions::from(&args);
+    if let Some(alert) = args.alert_precip_type {
+        if let Some(message) = weather_data.precipitation_alert(alert, &options) {
+            return Err(AppError::Alert(message));
+        }
+    }
+    if args.alert_severe {
+        if let Some(message) = weather_data.severe_weather_alert(&options) {
+            return Err(AppError::Alert(message));
+        }
+    }
+    if args.alert_fog {
+        if let Some(message) = weather_data.fog_alert(&options) {
+            return Err(AppError::Alert(message));
+        }
+    }
+    if args.alert_below_temp.is_some() || args.alert_above_temp.is_some() {
+        let (below, above) = (args.alert_below_temp, args.alert_above_temp);
+        if let Some(message) = weather_data.temperature_alert(below, above, &options) {
+            return Err(AppError::Alert(message));
+        }
+    }
+
     Ok(())
 }
+
+/// Adds the search for `query` to the location history file
+fn record_history(query: &str, location: &SavedLocation) -> Result<(), HistoryError> {
+    let mut history = LocationHistory::load()?;
+    history.record(query, location);
+    history.save()
+}
+
+/// Starts `$PAGER`, or `less` when it is not set, reading from a pipe; `None` when it cannot
+/// be started, so that the forecast is printed to stdout instead
+fn spawn_pager() -> Option<Child> {
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| String::from("less"));
+    // `$PAGER` is a command line such as `less -R`, quoted like in a shell
+    let words = match shell_words::split(&pager) {
+        Ok(words) => words,
+        Err(e) => {
+            eprintln!("warning: could not parse PAGER '{}': {}", pager, e);
+            return None;
+        }
+    };
+    let (program, arguments) = words.split_first()?;
+
+    match process::Command::new(program)
+        .args(arguments)
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => Some(child),
+        Err(e) => {
+            eprintln!("warning: could not start the pager '{}': {}", pager, e);
+            None
+        }
+    }
+}
+
+/// Prints the URLs the forecast for `query` would be fetched from
+fn print_dry_run(query: &str, args: &WeatherArgs, saved_locations: &SavedLocations) {
+    if let Ok(coords) = query.parse::<Coordinates>() {
+        println!("{}", weather::build_weather_url((coords.lat, coords.lon)));
+        return;
+    }
+
+    match saved_locations.get(query) {
+        Some(saved) => println!("{}", weather::build_weather_url((saved.lat, saved.lon))),
+        None => {
+            // the coordinates are only known once the geocoding request has been answered
+            println!("{}", geocoding::redacted_geocoding_url(query, args));
+            println!("{}?lat=<lat>&lon=<lon>", weather::WEATHER_API_URL);
+        }
+    }
+}
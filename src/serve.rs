@@ -0,0 +1,329 @@
+//! `weather-cli serve`: forecasts over HTTP for other programs
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::task::JoinSet;
+
+use crate::args::{ServeArgs, WeatherArgs};
+use crate::error::AppError;
+use crate::http::build_client;
+use crate::locations::{self, SavedLocations};
+use crate::weather::{self, WeatherData, WeatherError};
+
+/// Error statuses of `/weather` and when they are returned, as listed in `openapi_spec`
+const ERROR_RESPONSES: [(StatusCode, &str); 3] = [
+    (StatusCode::BAD_REQUEST, "The location is missing or not valid coordinates"),
+    (StatusCode::NOT_FOUND, "No place matches the location"),
+    (StatusCode::BAD_GATEWAY, "The geocoding or weather API failed"),
+];
+
+/// What the request handlers share
+pub(crate) struct Server {
+    client: reqwest::Client,
+    saved_locations: SavedLocations,
+    args: WeatherArgs,
+}
+
+impl Server {
+    pub(crate) fn new(args: WeatherArgs) -> Result<Server, AppError> {
+        Ok(Server {
+            client: build_client(&args).map_err(|e| AppError::network(e, &args))?,
+            saved_locations: SavedLocations::load()?,
+            args,
+        })
+    }
+
+    /// Forecast for `query`, which is resolved like the location argument of the CLI
+    pub(crate) async fn forecast(&self, query: &str) -> Result<WeatherData, AppError> {
+        let location =
+            locations::resolve_location(&self.client, query, &self.saved_locations, &self.args)
+                .await?;
+        let coords = (location.lat, location.lon);
+        weather::get_weather_data_with_client(&self.client, coords, &self.args)
+            .await
+            .map_err(|e| AppError::weather(e, &self.args))?
+            .ok_or(AppError::Weather(WeatherError::MissingData))
+    }
+
+    /// How the forecasts are shown, from the arguments of `weather-cli serve`
+    #[cfg(feature = "graphql")]
+    pub(crate) fn display_options(&self) -> weather::DisplayOptions {
+        weather::DisplayOptions::from(&self.args)
+    }
+}
+
+/// Answers HTTP requests, and gRPC and WebSocket ones when enabled, until the process is stopped
+pub async fn serve(args: WeatherArgs, serve_args: ServeArgs) -> Result<(), AppError> {
+    let server = Arc::new(Server::new(args)?);
+    let address = |port| SocketAddr::new(serve_args.address, port);
+
+    let mut servers = JoinSet::new();
+    servers.spawn(serve_http(Arc::clone(&server), address(serve_args.port)));
+    #[cfg(feature = "grpc")]
+    if serve_args.grpc {
+        let grpc = crate::grpc::serve_grpc(Arc::clone(&server), address(serve_args.grpc_port));
+        servers.spawn(grpc);
+    }
+    #[cfg(feature = "websocket")]
+    if serve_args.websocket {
+        let refresh = std::time::Duration::from_secs(serve_args.refresh_minutes * 60);
+        let websocket = crate::websocket::serve_websocket(
+            Arc::clone(&server),
+            address(serve_args.websocket_port),
+            refresh,
+        );
+        servers.spawn(websocket);
+    }
+
+    // the servers only return when they fail
+    while let Some(result) = servers.join_next().await {
+        result.expect("server task panicked")?;
+    }
+    Ok(())
+}
+
+async fn serve_http(server: Arc<Server>, address: SocketAddr) -> Result<(), AppError> {
+    let app = Router::new()
+        .route("/weather", get(get_weather))
+        .route("/openapi.json", get(get_openapi))
+        .with_state(Arc::clone(&server));
+    #[cfg(feature = "graphql")]
+    let app = app.merge(crate::graphql::router(server));
+
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    eprintln!("Listening on http://{}", address);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct WeatherQuery {
+    location: Option<String>,
+}
+
+async fn get_weather(
+    State(server): State<Arc<Server>>,
+    Query(query): Query<WeatherQuery>,
+) -> Response {
+    let Some(location) = query.location else {
+        return error_response(StatusCode::BAD_REQUEST, "missing the location parameter");
+    };
+    match server.forecast(&location).await {
+        Ok(weather_data) => Json(weather_data).into_response(),
+        Err(e) => error_response(error_status(&e), &e.to_string()),
+    }
+}
+
+async fn get_openapi() -> Response {
+    (
+        [(header::CONTENT_TYPE, "application/json")],
+        WeatherData::openapi_spec(),
+    )
+        .into_response()
+}
+
+fn error_status(e: &AppError) -> StatusCode {
+    match e {
+        AppError::Coordinates(_) => StatusCode::BAD_REQUEST,
+        AppError::LocationNotFound(_) => StatusCode::NOT_FOUND,
+        AppError::Network(_) | AppError::Weather(_) => StatusCode::BAD_GATEWAY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+impl WeatherData {
+    /// OpenAPI 3.1 description of the HTTP server, with the forecast schema of `json_schema`
+    pub fn openapi_spec() -> String {
+        let mut schemas = forecast_schemas();
+        schemas.insert(
+            String::from("Error"),
+            json!({
+                "type": "object",
+                "properties": { "error": { "type": "string" } },
+                "required": ["error"],
+            }),
+        );
+
+        let mut responses = serde_json::Map::new();
+        responses.insert(
+            String::from("200"),
+            json!({
+                "description": "The met.no forecast of the location",
+                "content": {
+                    "application/json": {
+                        "schema": { "$ref": "#/components/schemas/WeatherData" },
+                    },
+                },
+            }),
+        );
+        for (status, description) in ERROR_RESPONSES {
+            responses.insert(
+                status.as_u16().to_string(),
+                json!({
+                    "description": description,
+                    "content": {
+                        "application/json": {
+                            "schema": { "$ref": "#/components/schemas/Error" },
+                        },
+                    },
+                }),
+            );
+        }
+
+        let mut paths = serde_json::Map::new();
+        paths.insert(
+            String::from("/weather"),
+            json!({
+                "get": {
+                    "summary": "Forecast for a location",
+                    "parameters": [{
+                        "name": "location",
+                        "in": "query",
+                        "required": true,
+                        "description": "Place name, `lat,lon` or the alias of a saved location",
+                        "schema": { "type": "string" },
+                    }],
+                    "responses": responses,
+                },
+            }),
+        );
+        paths.insert(
+            String::from("/openapi.json"),
+            json!({
+                "get": {
+                    "summary": "This document",
+                    "responses": {
+                        "200": {
+                            "description": "OpenAPI 3.1 description of the server",
+                            "content": { "application/json": {} },
+                        },
+                    },
+                },
+            }),
+        );
+        #[cfg(feature = "graphql")]
+        paths.insert(
+            String::from("/graphql"),
+            json!({
+                "get": {
+                    "summary": "GraphQL query of the forecasts, see `graphql_schema`",
+                    "parameters": [{
+                        "name": "query",
+                        "in": "query",
+                        "required": true,
+                        "description": "e.g. `{ weather(location: \"Oslo\") { location } }`",
+                        "schema": { "type": "string" },
+                    }],
+                    "responses": {
+                        "200": {
+                            "description": "GraphQL response with the data or the errors",
+                            "content": { "application/json": {} },
+                        },
+                        "400": {
+                            "description": "The query parameters are not a GraphQL request",
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/Error" },
+                                },
+                            },
+                        },
+                    },
+                },
+            }),
+        );
+
+        let spec = json!({
+            "openapi": "3.1.0",
+            "info": {
+                "title": "weather-cli",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "paths": paths,
+            "components": { "schemas": schemas },
+        });
+        serde_json::to_string_pretty(&spec).expect("spec serializes to JSON")
+    }
+}
+
+/// `WeatherData` and the types it uses, with the references moved from the `$defs` of the JSON
+/// Schema to the components of the OpenAPI document
+fn forecast_schemas() -> serde_json::Map<String, Value> {
+    let mut root = schemars::schema_for!(WeatherData).to_value();
+    let root_object = root.as_object_mut().expect("schema is an object");
+    root_object.remove("$schema");
+    let mut schemas = match root_object.remove("$defs") {
+        Some(Value::Object(defs)) => defs,
+        _ => serde_json::Map::new(),
+    };
+    schemas.insert(String::from("WeatherData"), root);
+
+    for schema in schemas.values_mut() {
+        move_references(schema);
+    }
+    schemas
+}
+
+fn move_references(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                match (key.as_str(), value) {
+                    ("$ref", Value::String(reference)) => {
+                        *reference = reference.replace("#/$defs/", "#/components/schemas/");
+                    }
+                    (_, value) => move_references(value),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(move_references),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn references(value: &Value, found: &mut Vec<String>) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object {
+                    match (key.as_str(), value) {
+                        ("$ref", Value::String(reference)) => found.push(reference.clone()),
+                        (_, value) => references(value, found),
+                    }
+                }
+            }
+            Value::Array(values) => values.iter().for_each(|value| references(value, found)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn openapi_references_resolve_to_components() {
+        let spec: Value = serde_json::from_str(&WeatherData::openapi_spec()).unwrap();
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+
+        let mut found = Vec::new();
+        references(&spec, &mut found);
+        assert!(found.contains(&String::from("#/components/schemas/WeatherData")));
+        for reference in found {
+            let name = reference
+                .strip_prefix("#/components/schemas/")
+                .unwrap_or_else(|| panic!("{} is not a component", reference));
+            assert!(schemas.contains_key(name), "no schema for {}", reference);
+        }
+    }
+}
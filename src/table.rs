@@ -0,0 +1,110 @@
+use unicode_width::UnicodeWidthStr;
+
+/// Narrowest a column gets when the table is shrunk to fit `max_width`
+const MIN_COLUMN_WIDTH: usize = 3;
+
+/// Table with box-drawing borders, its columns as wide as their widest cell
+///
+/// When the table would be wider than `max_width`, the widest columns are narrowed
+/// and their cells truncated with `…`.
+pub fn render_table(headers: &[&str], rows: &[Vec<String>], max_width: Option<usize>) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.width()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.width());
+        }
+    }
+
+    if let Some(max_width) = max_width {
+        // every column takes its width plus a space on each side and a border
+        let borders = 3 * widths.len() + 1;
+        while widths.iter().sum::<usize>() + borders > max_width {
+            let widest = widths
+                .iter_mut()
+                .max()
+                .filter(|width| **width > MIN_COLUMN_WIDTH);
+            match widest {
+                Some(width) => *width -= 1,
+                None => break,
+            }
+        }
+    }
+
+    let line = |left: &str, middle: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|width| "─".repeat(width + 2)).collect();
+        format!("{}{}{}\n", left, segments.join(middle), right)
+    };
+    let row = |cells: &mut dyn Iterator<Item = &str>| {
+        let cells: Vec<String> = cells
+            .zip(&widths)
+            .map(|(cell, width)| pad(&truncate(cell, *width), *width))
+            .collect();
+        format!("│ {} │\n", cells.join(" │ "))
+    };
+
+    let mut output = line("┌", "┬", "┐");
+    output.push_str(&row(&mut headers.iter().copied()));
+    output.push_str(&line("├", "┼", "┤"));
+    for cells in rows {
+        output.push_str(&row(&mut cells.iter().map(String::as_str)));
+    }
+    output.push_str(&line("└", "┴", "┘"));
+    output
+}
+
+/// `text` cut down to `width` terminal columns, ending with `…` when it was cut
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+
+    let mut truncated = String::new();
+    for c in text.chars() {
+        truncated.push(c);
+        // measured as a whole, since emoji variation selectors widen the character before them
+        if truncated.width() + 1 > width {
+            truncated.pop();
+            break;
+        }
+    }
+    truncated.push('…');
+    truncated
+}
+
+fn pad(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(text.width())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_cuts_to_the_width_with_an_ellipsis() {
+        assert_eq!(truncate("Cloudy", 6), "Cloudy");
+        assert_eq!(truncate("Partly cloudy", 8), "Partly …");
+        // the variation selector makes the sun two columns wide
+        assert_eq!(truncate("☀️ Clear Sky", 3), "☀️…");
+    }
+
+    #[test]
+    fn pad_fills_up_to_the_terminal_width() {
+        assert_eq!(pad("5°C", 5), "5°C  ");
+        assert_eq!(pad("☀️", 3), "☀️ ");
+        assert_eq!(pad("Cloudy", 3), "Cloudy");
+    }
+
+    #[test]
+    fn render_table_shrinks_the_widest_column_to_fit() {
+        let rows = vec![vec!["00:00".to_string(), "Partly cloudy".to_string()]];
+        assert_eq!(
+            render_table(&["Time", "Weather"], &rows, Some(20)),
+            "┌───────┬──────────┐\n\
+             │ Time  │ Weather  │\n\
+             ├───────┼──────────┤\n\
+             │ 00:00 │ Partly … │\n\
+             └───────┴──────────┘\n"
+        );
+        assert!(render_table(&["Time", "Weather"], &rows, None).contains("│ Partly cloudy │"));
+    }
+}
@@ -1,15 +1,34 @@
-use crate::args::{DurationType, OutputMode};
-use chrono::{DateTime, Datelike, Duration, Utc};
-use serde::{de::Error, Deserialize, Serialize};
+use crate::args::{
+    ColumnField, DurationType, OutputMode, PrecipitationAlert, PressureUnit, SpeedUnit, TempUnit,
+    WeatherArgs,
+};
+use clap::ValueEnum;
+use crate::http::{build_client, fetch_with_retry, retry_after};
+use crate::color::{self, Palette};
+use crate::condition::{TimeVariant, WeatherCondition};
+use crate::{charts, table};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveTime, TimeZone, Timelike,
+    Utc, Weekday,
+};
+use chrono_tz::Tz;
+use reqwest::StatusCode;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::f64::consts::PI;
 use std::fmt::{self, Display};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{IsTerminal, Write};
+use url::Url;
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct Geometry {
     pub r#type: String,
     pub coordinates: Vec<f64>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize, JsonSchema)]
 pub struct Units {
     pub air_pressure_at_sea_level: Option<String>,
     pub air_temperature: Option<String>,
@@ -20,51 +39,173 @@ pub struct Units {
     pub wind_speed: Option<String>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct Meta {
     pub updated_at: DateTime<Utc>,
     pub units: Units,
 }
 
-#[derive(Deserialize, Serialize)]
+/// One indented `field: unit` line per unit given by met.no
+impl Display for Units {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = [
+            ("air_temperature", &self.air_temperature),
+            ("air_pressure_at_sea_level", &self.air_pressure_at_sea_level),
+            ("cloud_area_fraction", &self.cloud_area_fraction),
+            ("precipitation_amount", &self.precipitation_amount),
+            ("relative_humidity", &self.relative_humidity),
+            ("wind_from_direction", &self.wind_from_direction),
+            ("wind_speed", &self.wind_speed),
+        ];
+        for (field, unit) in fields {
+            if let Some(unit) = unit {
+                writeln!(f, "  {}: {}", field, unit)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Update time of the forecast in the local time zone, followed by the units
+impl Display for Meta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let updated_at = self.updated_at.with_timezone(&Local);
+        writeln!(f, "Updated: {}", updated_at.format("%Y-%m-%d %H:%M"))?;
+        writeln!(f, "Units:")?;
+        self.units.fmt(f)
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema)]
 pub struct Details {
     pub air_pressure_at_sea_level: Option<f64>,
     pub air_temperature: Option<f64>,
     pub cloud_area_fraction: Option<f64>,
+    pub precipitation_amount: Option<f64>,
     pub relative_humidity: Option<f64>,
     pub wind_from_direction: Option<f64>,
     pub wind_speed: Option<f64>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct Summary {
     pub symbol_code: String,
 }
 
-#[derive(Deserialize, Serialize)]
+/// met.no symbol code such as `partlycloudy_night`: a condition and, for conditions that
+/// depend on the sun, a `_day`, `_night` or `_polartwilight` suffix
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SymbolCode<'a>(pub &'a str);
+
+/// Kind of precipitation in a weather condition
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PrecipitationType {
+    None,
+    Rain,
+    Sleet,
+    Snow,
+}
+
+impl Display for PrecipitationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrecipitationType::None => write!(f, "no precipitation"),
+            PrecipitationType::Rain => write!(f, "rain"),
+            PrecipitationType::Sleet => write!(f, "sleet"),
+            PrecipitationType::Snow => write!(f, "snow"),
+        }
+    }
+}
+
+impl<'a> SymbolCode<'a> {
+    /// The condition without its time of day suffix, e.g. `partlycloudy`
+    pub fn base_condition(&self) -> &'a str {
+        self.0
+            .split_once('_')
+            .map_or(self.0, |(condition, _)| condition)
+    }
+
+    /// Whether the condition has rain, sleet or snow in it
+    pub fn precipitation_type(&self) -> PrecipitationType {
+        let condition = self.base_condition();
+        if condition.contains("sleet") {
+            PrecipitationType::Sleet
+        } else if condition.contains("snow") {
+            PrecipitationType::Snow
+        } else if condition.contains("rain") {
+            PrecipitationType::Rain
+        } else {
+            PrecipitationType::None
+        }
+    }
+
+    /// How bad the weather is: 0 for clear or fair, 1 for clouds, fog or light precipitation,
+    /// 2 for precipitation and 3 for heavy precipitation or thunder
+    pub fn severity(&self) -> u8 {
+        let condition = self.base_condition();
+        if condition.contains("thunder") || condition.starts_with("heavy") {
+            3
+        } else if self.precipitation_type() == PrecipitationType::None {
+            match condition {
+                "clearsky" | "fair" => 0,
+                _ => 1,
+            }
+        } else if condition.starts_with("light") {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// The main word of the condition, e.g. `Rain` for `lightrainshowers_day`
+    pub fn short_description(&self) -> &'static str {
+        let condition = self.base_condition();
+        [
+            ("thunder", "Thunder"),
+            ("sleet", "Sleet"),
+            ("snow", "Snow"),
+            ("rain", "Rain"),
+            ("fog", "Fog"),
+            ("cloudy", "Cloudy"),
+            ("fair", "Fair"),
+            ("clearsky", "Clear"),
+        ]
+        .iter()
+        .find(|(keyword, _)| condition.contains(keyword))
+        .map_or("Unknown", |(_, word)| word)
+    }
+}
+
+impl Summary {
+    pub fn symbol(&self) -> SymbolCode<'_> {
+        SymbolCode(&self.symbol_code)
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct Next12Hours {
     pub summary: Summary,
     pub details: Option<Details>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct Next1Hour {
     pub summary: Summary,
     pub details: Option<Details>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct Next6Hours {
     pub summary: Summary,
     pub details: Option<Details>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct Instant {
     pub details: Details,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct Data {
     pub instant: Instant,
     pub next_12_hours: Option<Next12Hours>,
@@ -72,43 +213,68 @@ pub struct Data {
     pub next_6_hours: Option<Next6Hours>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct Timeseries {
     pub time: DateTime<Utc>,
     pub data: Data,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct Properties {
     pub meta: Meta,
     pub timeseries: Vec<Timeseries>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct WeatherData {
     pub r#type: String,
     pub geometry: Geometry,
     pub properties: Properties,
 }
 
-pub async fn get_weather_data(coords: (f64, f64)) -> Result<Option<WeatherData>, reqwest::Error> {
+pub const WEATHER_API_URL: &str = "https://api.met.no/weatherapi/locationforecast/2.0/compact";
+
+pub fn build_weather_url(coords: (f64, f64)) -> Url {
     let query_params = [
-        ("lat", coords.0),
-        ("lon", coords.1),
+        ("lat", coords.0.to_string()),
+        ("lon", coords.1.to_string()),
         // ("timestep", String::from("1h")),
         // ("timeoffsets", String::from("1h")),
         // ("units", String::from("si")),
     ];
-    // println!("{:?}", query_params);
 
-    let weather_ans: WeatherData = reqwest::Client::new()
-        .get("https://api.met.no/weatherapi/locationforecast/2.0/compact?")
-        .header("User-Agent", "Weather-Cli/0.0.1")
-        .query(&query_params)
-        .send()
-        .await?
-        .json()
-        .await?;
+    Url::parse_with_params(WEATHER_API_URL, &query_params).expect("weather url is valid")
+}
+
+pub async fn get_weather_data(
+    coords: (f64, f64),
+    args: &WeatherArgs,
+) -> Result<Option<WeatherData>, WeatherError> {
+    get_weather_data_with_client(&build_client(args)?, coords, args).await
+}
+
+pub async fn get_weather_data_with_client(
+    client: &reqwest::Client,
+    coords: (f64, f64),
+    args: &WeatherArgs,
+) -> Result<Option<WeatherData>, WeatherError> {
+    let request = client.get(build_weather_url(coords));
+
+    let response = fetch_with_retry(
+        client,
+        request,
+        args.retries.unwrap(),
+        args.retry_delay_ms.unwrap(),
+    )
+    .await?;
+
+    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+        return Err(WeatherError::RateLimited {
+            retry_after: retry_after(&response),
+        });
+    }
+
+    let weather_ans: WeatherData = response.error_for_status()?.json().await?;
 
     // println!("{}", serde_json::to_string(&weather_ans).unwrap());
     // let text = weather_ans.text().await?;
@@ -116,307 +282,2292 @@ pub async fn get_weather_data(coords: (f64, f64)) -> Result<Option<WeatherData>,
     Ok(Some(weather_ans))
 }
 
+/// CSV header for `columns`, every field when it is empty
+pub fn csv_header(columns: &[ColumnField]) -> String {
+    let mut header = vec!["time"];
+    header.extend(selected_columns(columns).iter().map(|column| match column {
+        ColumnField::Weather => "symbol_code",
+        ColumnField::Temperature => "air_temperature",
+        ColumnField::WindSpeed => "wind_speed",
+        ColumnField::WindDirection => "wind_from_direction",
+        ColumnField::Humidity => "relative_humidity",
+        ColumnField::CloudCover => "cloud_area_fraction",
+        ColumnField::Pressure => "air_pressure_at_sea_level",
+        ColumnField::Precipitation => "precipitation_amount",
+    }));
+    header.join(",")
+}
+
+/// `--columns`, falling back to every field when none were given
+fn selected_columns(columns: &[ColumnField]) -> &[ColumnField] {
+    match columns.is_empty() {
+        true => ColumnField::value_variants(),
+        false => columns,
+    }
+}
+
 #[derive(Debug)]
 pub enum WeatherError {
     MissingData,
+    Network(reqwest::Error),
+    RateLimited { retry_after: Option<std::time::Duration> },
+    StaleData { age: Duration, max_age: Duration },
+    InvalidDateRange { start: NaiveDate, end: NaiveDate },
+    OutsideForecast { first: NaiveDate, last: NaiveDate },
+    Io(std::io::Error),
+    Export(String),
 }
 
 impl Display for WeatherError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             WeatherError::MissingData => write!(f, "Missing data"),
+            WeatherError::Network(e) => write!(f, "Network error: {}", e),
+            WeatherError::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(
+                f,
+                "Rate limited by the weather API, retry in {} seconds",
+                retry_after.as_secs()
+            ),
+            WeatherError::RateLimited { retry_after: None } => {
+                write!(f, "Rate limited by the weather API")
+            }
+            WeatherError::StaleData { age, max_age } => write!(
+                f,
+                "Forecast is {} minutes old (max allowed: {} minutes, see --max-age)",
+                age.num_minutes(),
+                max_age.num_minutes()
+            ),
+            WeatherError::InvalidDateRange { start, end } => {
+                write!(f, "Start date {} is after end date {}", start, end)
+            }
+            WeatherError::OutsideForecast { first, last } => {
+                write!(f, "The forecast only covers {} to {}", first, last)
+            }
+            WeatherError::Io(e) => write!(f, "Could not write output: {}", e),
+            WeatherError::Export(e) => write!(f, "Could not export forecast: {}", e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for WeatherError {
+    fn from(e: reqwest::Error) -> Self {
+        WeatherError::Network(e)
+    }
+}
+
+impl From<std::io::Error> for WeatherError {
+    fn from(e: std::io::Error) -> Self {
+        WeatherError::Io(e)
+    }
+}
+
+/// Presentation settings shared by every output mode
+#[derive(Clone, Debug, Default)]
+pub struct DisplayOptions {
+    pub temp_unit: TempUnit,
+    pub pressure_unit: PressureUnit,
+    pub speed_unit: SpeedUnit,
+    pub feels_like: bool,
+    pub no_emoji: bool,
+    pub show_units: bool,
+    pub columns: Vec<ColumnField>,
+    pub width: Option<usize>,
+    /// Colors of the output, `None` to leave it uncolored
+    pub palette: Option<Palette>,
+    /// Time zone of the displayed times, UTC when `None`
+    pub timezone: Option<Tz>,
+    /// Hours the forecast is shown ahead of the current time
+    pub skip_hours: u32,
+}
+
+impl From<&WeatherArgs> for DisplayOptions {
+    fn from(args: &WeatherArgs) -> Self {
+        DisplayOptions {
+            temp_unit: args.temp_unit.unwrap_or(args.unit_system().temp_unit()),
+            pressure_unit: args.pressure_unit.unwrap_or(args.unit_system().pressure_unit()),
+            speed_unit: args.speed_unit.unwrap_or(args.unit_system().speed_unit()),
+            feels_like: args.feels_like,
+            no_emoji: args.no_emoji,
+            show_units: args.show_units,
+            columns: args.columns.clone(),
+            width: Some(args.width.unwrap_or_else(terminal_width)),
+            // escape codes would end up in files and pipes
+            palette: std::io::stdout()
+                .is_terminal()
+                .then(|| args.color_theme.unwrap().palette()),
+            timezone: args.timezone,
+            skip_hours: args.skip_hours.unwrap_or(0),
         }
     }
 }
 
+impl DisplayOptions {
+    /// Time the forecast is shown from: the current time, moved ahead by `skip_hours`, or the
+    /// latest time chrono can represent when that is past it
+    pub fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+            .checked_add_signed(Duration::hours(self.skip_hours as i64))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+}
+
+impl Details {
+    /// Dew point in °C from the Magnus formula; met.no does not give it in this API
+    pub fn dew_point_temperature(&self) -> Option<f64> {
+        const A: f64 = 17.62;
+        const B: f64 = 243.12;
+        let temperature = self.air_temperature?;
+        let humidity = self.relative_humidity?;
+        if humidity <= 0.0 {
+            return None;
+        }
+        let gamma = (humidity / 100.0).ln() + A * temperature / (B + temperature);
+        Some(B * gamma / (A - gamma))
+    }
+
+    /// Water vapour in the air in g/m³, from the Tetens formula for the saturation vapour
+    /// pressure
+    pub fn absolute_humidity_g_per_m3(&self) -> Option<f64> {
+        let temperature = self.air_temperature?;
+        let humidity = self.relative_humidity?;
+        let saturation_hpa = 6.1078 * 10f64.powf(7.5 * temperature / (temperature + 237.3));
+        let vapour_hpa = humidity / 100.0 * saturation_hpa;
+        Some(216.7 * vapour_hpa / (temperature + 273.15))
+    }
+
+    /// Perceived temperature in °C: wind chill when cold and windy, heat index when hot and humid
+    pub fn feels_like_temperature(&self) -> Option<f64> {
+        let temperature = self.air_temperature?;
+
+        if temperature <= 10.0 {
+            // Environment Canada wind chill, with the wind speed in km/h
+            let wind_speed = self.wind_speed? * 3.6;
+            if wind_speed < 4.8 {
+                return None;
+            }
+            let wind = wind_speed.powf(0.16);
+            return Some(13.12 + 0.6215 * temperature - 11.37 * wind + 0.3965 * temperature * wind);
+        }
+
+        if temperature >= 27.0 {
+            // Rothfusz regression of the heat index, defined in °F
+            let humidity = self.relative_humidity?;
+            if humidity < 40.0 {
+                return None;
+            }
+            let t = temperature * 9.0 / 5.0 + 32.0;
+            let heat_index = -42.379 + 2.04901523 * t + 10.14333127 * humidity
+                - 0.22475541 * t * humidity
+                - 0.00683783 * t * t
+                - 0.05481717 * humidity * humidity
+                + 0.00122874 * t * t * humidity
+                + 0.00085282 * t * humidity * humidity
+                - 0.00000199 * t * t * humidity * humidity;
+            return Some((heat_index - 32.0) * 5.0 / 9.0);
+        }
+
+        None
+    }
+}
+
+impl Properties {
+    /// Entries in the seven days starting at `from`, regardless of month boundaries
+    pub fn timeseries_for_week(&self, from: DateTime<Utc>) -> Vec<&Timeseries> {
+        let cutoff = from + Duration::days(7);
+        self.timeseries
+            .iter()
+            .filter(|timeseries| timeseries.time >= from && timeseries.time < cutoff)
+            .collect()
+    }
+
+    /// Entries on `date` in `timezone`, or on the UTC date when it is `None`
+    pub fn timeseries_for_day(&self, date: NaiveDate, timezone: Option<Tz>) -> Vec<&Timeseries> {
+        let (from, to) = local_day_bounds(date, timezone);
+        self.timeseries
+            .iter()
+            .filter(|timeseries| timeseries.time >= from && timeseries.time < to)
+            .collect()
+    }
+}
+
+impl Timeseries {
+    pub(crate) fn summary(&self, duration: DurationType) -> Result<&Summary, WeatherError> {
+        match duration {
+            DurationType::Week | DurationType::Weekend | DurationType::Custom { .. } => {
+                self.data.next_6_hours.as_ref().map(|next| &next.summary)
+            }
+            // met.no only gives 6 hour periods a few days ahead
+            DurationType::HoursAhead(_) => self
+                .data
+                .next_1_hours
+                .as_ref()
+                .map(|next| &next.summary)
+                .or(self.data.next_6_hours.as_ref().map(|next| &next.summary)),
+            _ => self.data.next_1_hours.as_ref().map(|next| &next.summary),
+        }
+        .ok_or(WeatherError::MissingData)
+    }
+
+    /// Whether the sun is up according to the symbol code of the next hour (or the next 6 hours
+    /// far ahead), `None` when the condition does not tell
+    pub fn is_daytime(&self) -> Option<bool> {
+        let summary = self
+            .summary(DurationType::Today)
+            .or_else(|_| self.summary(DurationType::Week))
+            .ok()?;
+        match WeatherCondition::try_from(summary.symbol()).ok()?.time_of_day() {
+            TimeVariant::Day => Some(true),
+            TimeVariant::Night | TimeVariant::PolarTwilight => Some(false),
+            TimeVariant::AllDay => None,
+        }
+    }
+
+    pub fn local_time(&self, tz: &Tz) -> DateTime<Tz> {
+        self.time.with_timezone(tz)
+    }
+
+    pub(crate) fn details(&self) -> &Details {
+        &self.data.instant.details
+    }
+
+    /// Precipitation expected over the period matching `duration`, in mm
+    pub(crate) fn precipitation_amount(&self, duration: DurationType) -> Option<f64> {
+        let details = match duration {
+            DurationType::Week | DurationType::Weekend | DurationType::Custom { .. } => {
+                self.data.next_6_hours.as_ref()?.details.as_ref()
+            }
+            DurationType::HoursAhead(_) => match &self.data.next_1_hours {
+                Some(next) => next.details.as_ref(),
+                None => self.data.next_6_hours.as_ref()?.details.as_ref(),
+            },
+            _ => self.data.next_1_hours.as_ref()?.details.as_ref(),
+        };
+        details?.precipitation_amount
+    }
+}
+
 impl WeatherData {
+    /// JSON Schema of the forecast as returned by met.no and serialized by this crate
+    pub fn json_schema() -> String {
+        serde_json::to_string_pretty(&schemars::schema_for!(WeatherData))
+            .expect("schema serializes to JSON")
+    }
+
+    /// Time since met.no last updated the forecast
+    pub fn age(&self) -> Duration {
+        Utc::now() - self.properties.meta.updated_at
+    }
+
+    /// Combines two forecasts for the same place, e.g. a cached one and a fresh one
+    ///
+    /// Entries at the same time are taken from whichever forecast met.no updated last, and
+    /// entries only in `update` are added in time order.
+    pub fn merge(mut base: WeatherData, update: WeatherData) -> WeatherData {
+        let update_is_newer = update.properties.meta.updated_at >= base.properties.meta.updated_at;
+        let timeseries = &mut base.properties.timeseries;
+        for entry in update.properties.timeseries {
+            match timeseries.binary_search_by_key(&entry.time, |timeseries| timeseries.time) {
+                Ok(index) if update_is_newer => timeseries[index] = entry,
+                Ok(_) => {}
+                Err(index) => timeseries.insert(index, entry),
+            }
+        }
+        if update_is_newer {
+            base.properties.meta = update.properties.meta;
+        }
+        base
+    }
+
+    /// Fails with `WeatherError::StaleData` when the forecast is older than `max_age`
+    pub fn check_age(&self, max_age: Duration) -> Result<(), WeatherError> {
+        let age = self.age();
+        match age > max_age {
+            true => Err(WeatherError::StaleData { age, max_age }),
+            false => Ok(()),
+        }
+    }
+
+    /// Fails unless `start` is no later than `end` and both days are covered by the forecast
+    pub fn check_date_range(&self, start: NaiveDate, end: NaiveDate) -> Result<(), WeatherError> {
+        if start > end {
+            return Err(WeatherError::InvalidDateRange { start, end });
+        }
+
+        let timeseries = &self.properties.timeseries;
+        let (Some(first), Some(last)) = (timeseries.first(), timeseries.last()) else {
+            return Err(WeatherError::MissingData);
+        };
+        let (first, last) = (first.time.date_naive(), last.time.date_naive());
+        match start >= first && end <= last {
+            true => Ok(()),
+            false => Err(WeatherError::OutsideForecast { first, last }),
+        }
+    }
+
+    /// Hash of the serialized forecast, to detect a corrupted copy of it
+    pub fn checksum(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        serde_json::to_vec(self)
+            .expect("forecast serializes to JSON")
+            .hash(&mut hasher);
+        hasher.finish()
+    }
+
     pub fn display(
         &self,
         duration: DurationType,
         location_name: String,
         output_mode: OutputMode,
+        options: &DisplayOptions,
+    ) -> Result<(), WeatherError> {
+        let mut stdout = std::io::stdout().lock();
+        self.display_to(duration, location_name, output_mode, options, &mut stdout)
+    }
+
+    /// Same as `display`, writing to `writer` instead of stdout
+    pub fn display_to<W: Write>(
+        &self,
+        duration: DurationType,
+        location_name: String,
+        output_mode: OutputMode,
+        options: &DisplayOptions,
+        writer: &mut W,
     ) -> Result<(), WeatherError> {
+        if options.show_units {
+            writeln!(writer, "{}", format_units_legend(&self.properties.meta.units, options))?;
+        }
+
+        if output_mode == OutputMode::Compact {
+            self.streaming_display(duration, &location_name, options, writer)?;
+            writeln!(writer)?;
+            return Ok(());
+        }
 
-        let output = match output_mode {
-            OutputMode::Compact => self.display_compact(duration, location_name),
-            OutputMode::Detailed => self.display_detailed(duration, location_name),
-            OutputMode::Complete => self.display_complete(duration, location_name),
-        }?;
-        
-        println!("{}", output);
+        let output = self.format(duration, location_name, output_mode, options)?;
+
+        // minimal and status-line output is meant to be captured by scripts, so it has no
+        // trailing newline
+        match output_mode {
+            OutputMode::Minimal | OutputMode::Tmux | OutputMode::Xmobar => {
+                write!(writer, "{}", output)?
+            }
+            _ => writeln!(writer, "{}", output)?,
+        }
         Ok(())
     }
 
-    fn display_complete(
+    /// Writes the compact forecast line by line instead of building it in memory first
+    pub fn streaming_display<W: Write>(
         &self,
         duration: DurationType,
-        location_name: String,
-    ) -> Result<String, WeatherError> {
-        Ok(String::new())
+        location: &str,
+        options: &DisplayOptions,
+        writer: &mut W,
+    ) -> Result<(), WeatherError> {
+        let current_time = options.now();
+        let header = format!(
+            "{} (updated {})",
+            view_header(duration, location, options).trim_end(),
+            format_age(self.age()),
+        );
+        writeln!(writer, "{}", fit_width(&header, options))?;
+        let mut day = None;
+        for timeseries in self.lazy_timeseries(duration, current_time, options.timezone) {
+            if let Some(heading) = self.day_heading(duration, timeseries, &mut day, options) {
+                writeln!(writer, "{}", heading)?;
+            }
+            writeln!(
+                writer,
+                "{}",
+                format_line(
+                    &format_time_prefix(duration, entry_time(timeseries, options)),
+                    &timeseries.summary(duration)?.symbol_code,
+                    &format!(
+                        " {}",
+                        format_details_temperature(
+                            &self.displayed_details(timeseries, duration, current_time),
+                            options
+                        )?
+                    ),
+                    options,
+                ),
+            )?;
+        }
+        Ok(())
     }
 
-    fn display_detailed(
+    pub fn format(
         &self,
         duration: DurationType,
         location_name: String,
+        output_mode: OutputMode,
+        options: &DisplayOptions,
     ) -> Result<String, WeatherError> {
-        Ok(String::new())
+        match output_mode {
+            OutputMode::Compact => self.display_compact(duration, location_name, options),
+            OutputMode::Detailed => self.display_detailed(duration, location_name, options),
+            OutputMode::Complete => self.display_complete(duration, location_name, options),
+            OutputMode::Minimal => match duration {
+                DurationType::HoursAhead(hours) => self.display_minimal_hours(hours, options),
+                _ => self.display_minimal(options),
+            },
+            OutputMode::Tmux => self.display_tmux(options),
+            OutputMode::Xmobar => self.display_xmobar(options),
+            OutputMode::Table => self.display_table(duration, location_name, options),
+            OutputMode::KindleMd => Ok(self.to_kindle_markdown(duration, &location_name, options)),
+            OutputMode::LedgerComment => {
+                Ok(self.display_ledger_comment(duration, &location_name, options))
+            }
+            OutputMode::Orgzly => Ok(self.to_orgzly_note(duration, &location_name, options)),
+            OutputMode::Obsidian => Ok(self.to_obsidian_note(duration, &location_name, options)),
+            OutputMode::Notion => Ok(self.to_notion_markdown(duration, &location_name, options)),
+            OutputMode::Logseq => Ok(self.to_logseq_blocks(duration, &location_name, options)),
+            OutputMode::Roam => Ok(self.to_roam_block(&location_name, options)),
+            OutputMode::Prometheus => Ok(self.to_prometheus_metrics(&location_name, options)),
+            OutputMode::Conky => Ok(self.to_conky_template(&location_name, options)),
+            OutputMode::Compare => self.display_day_comparison(&location_name, options),
+            OutputMode::Dendron => {
+                let today = display_time(options.now(), options).date_naive();
+                let date = match duration {
+                    DurationType::Tomorrow => today + Duration::days(1),
+                    DurationType::Custom { start, .. } => start,
+                    _ => today,
+                };
+                Ok(self.to_dendron_frontmatter(&location_name, date, options))
+            }
+            #[cfg(feature = "polars")]
+            OutputMode::PolarsCsv => self.display_polars_csv(duration),
+        }
     }
 
-    fn display_compact(
+    /// Timeseries entries covered by `duration`, borrowed from the forecast; the days of the
+    /// day views are those of `timezone`, or UTC days when it is `None`
+    pub fn lazy_timeseries(
         &self,
         duration: DurationType,
-        location_name: String,
-    ) -> Result<String, WeatherError> {
-        let mut output = String::new();
-        let current_time = DateTime::<Utc>::from(Utc::now());
-        output.push_str(&format!("Weather for {} ", location_name));
-        match duration {
-            DurationType::Now => {
-                output.push_str(&format!("at {}\n", current_time.format("%H:%M")));
-                // select the timeseries that is closest to the current time
-                // print the summary and the temperature
-                let closest_timeseries = self
-                    .properties
-                    .timeseries
-                    .iter()
-                    .min_by_key(|timeseries| (timeseries.time - current_time).num_seconds().abs())
-                    .unwrap();
-                output.push_str(&format!(
-                    "{} {}°C\n",
-                    format_weather_description(
-                        closest_timeseries
-                            .data
-                            .next_1_hours
-                            .as_ref()
-                            .unwrap()
-                            .summary
-                            .symbol_code
-                            .as_str()
-                    ),
-                    closest_timeseries
-                        .data
-                        .instant
-                        .details
-                        .air_temperature
-                        .as_ref()
-                        .unwrap()
-                ));
+        now: DateTime<Utc>,
+        timezone: Option<Tz>,
+    ) -> impl Iterator<Item = &Timeseries> {
+        let today = local_date(now, timezone);
+        let all = self.properties.timeseries.iter();
+
+        let entries: Box<dyn Iterator<Item = &Timeseries>> = match duration {
+            DurationType::Now => Box::new(self.next_n_hours(now, 1)),
+            DurationType::NextHour => {
+                Box::new(self.get_timeseries_at(now + Duration::hours(1)).into_iter())
             }
+            DurationType::Today => Box::new(
+                self.properties
+                    .timeseries_for_day(today, timezone)
+                    .into_iter(),
+            ),
+            DurationType::Tomorrow => Box::new(
+                self.properties
+                    .timeseries_for_day(today + Duration::days(1), timezone)
+                    .into_iter(),
+            ),
+            // one entry per 6 hour block, summarized by its `next_6_hours`
+            DurationType::Week => Box::new(
+                self.properties
+                    .timeseries_for_week(local_day_bounds(today, timezone).0)
+                    .into_iter()
+                    .filter(|timeseries| timeseries.time.hour() % 6 == 0),
+            ),
+            // 6-hourly entries, like the ones met.no gives a few days ahead
+            DurationType::Weekend => {
+                let saturday = weekend_start(today);
+                Box::new(all.filter(move |timeseries| {
+                    let date = local_date(timeseries.time, timezone);
+                    (date == saturday || date == saturday + Duration::days(1))
+                        && timeseries.time.hour() % 6 == 0
+                }))
+            }
+            DurationType::Morning
+            | DurationType::Afternoon
+            | DurationType::Evening
+            | DurationType::Night => {
+                let (from, to) = time_slice_bounds(duration, now).unwrap();
+                Box::new(self.get_timeseries_range(from, to))
+            }
+            // 6 hour blocks, like the week
+            DurationType::Custom { start, end } => Box::new(all.filter(move |timeseries| {
+                (start..=end).contains(&local_date(timeseries.time, timezone))
+                    && timeseries.time.hour() % 6 == 0
+            })),
+            // every entry of the N hours starting with the current one
+            DurationType::HoursAhead(hours) => Box::new(all.filter(move |timeseries| {
+                timeseries.time > now - Duration::hours(1)
+                    && timeseries.time <= now + Duration::hours(hours as i64 - 1)
+            })),
+        };
+        entries
+    }
 
-            DurationType::Today => {
-                output.push_str(&format!("on {}\n", current_time.format("%A, %d %B")));
-                // select every timeseries that is today
-                // for each timeseries, print the time and the summary
-                for timeseries in self.properties.timeseries.iter() {
-                    if timeseries.time.day() == current_time.day() {
-                        output.push_str(&format!(
-                            "{}: {} {}°C\n",
-                            timeseries.time.format("%H:%M"),
-                            format_weather_description(
-                                timeseries
-                                    .data
-                                    .next_1_hours
-                                    .as_ref()
-                                    .unwrap()
-                                    .summary
-                                    .symbol_code
-                                    .as_str()
-                            ),
-                            timeseries
-                                .data
-                                .instant
-                                .details
-                                .air_temperature
-                                .as_ref()
-                                .unwrap()
-                        ));
-                    }
-                }
+    /// Heading with the temperature range of the day of `timeseries` when the weekend view
+    /// reaches a new day, `day` being the day of the previous heading
+    fn day_heading(
+        &self,
+        duration: DurationType,
+        timeseries: &Timeseries,
+        day: &mut Option<NaiveDate>,
+        options: &DisplayOptions,
+    ) -> Option<String> {
+        let date = entry_time(timeseries, options).date_naive();
+        if duration != DurationType::Weekend || *day == Some(date) {
+            return None;
+        }
+        *day = Some(date);
+
+        let (from, to) = local_day_bounds(date, options.timezone);
+        let series = self.get_hourly_temperature_series(from, to);
+        let temperatures = series.iter().map(|(_, temperature)| *temperature);
+        let heading = date.format("%A, %d %B").to_string();
+        match (temperatures.clone().reduce(f64::min), temperatures.reduce(f64::max)) {
+            (Some(min), Some(max)) => Some(format!(
+                "{}: {} to {}",
+                heading,
+                format_temperature(min, options.temp_unit),
+                format_temperature(max, options.temp_unit),
+            )),
+            _ => Some(heading),
+        }
+    }
+
+    /// Rough chance of precipitation for every hour between `from` and `to`, estimated from the
+    /// forecast amount since met.no gives no probabilities in this API
+    pub fn get_hourly_precipitation_probability(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, f64)> {
+        self.get_timeseries_range(from, to)
+            .filter_map(|timeseries| {
+                let amount = timeseries.precipitation_amount(DurationType::Today)?;
+                Some((timeseries.time, precipitation_probability(amount)))
+            })
+            .collect()
+    }
+
+    /// Air temperature at `at`, interpolated linearly between the entries before and after it
+    pub fn interpolate_temperature(&self, at: DateTime<Utc>) -> Option<f64> {
+        self.properties.timeseries.windows(2).find_map(|pair| {
+            let (before, after) = (&pair[0], &pair[1]);
+            if at < before.time || at > after.time {
+                return None;
             }
-            DurationType::Tomorrow => {
-                output.push_str(&format!("on {}\n", current_time.format("%A, %d %B")));
-                // select every timeseries that is tomorrow
-                // for each timeseries, print the time and the summary
-                for timeseries in self.properties.timeseries.iter() {
-                    if timeseries.time.day() == current_time.day() + 1 {
-                        output.push_str(&format!(
-                            "{}: {} {}°C\n",
-                            timeseries.time.format("%H:%M"),
-                            format_weather_description(
-                                timeseries
-                                    .data
-                                    .next_1_hours
-                                    .as_ref()
-                                    .unwrap()
-                                    .summary
-                                    .symbol_code
-                                    .as_str()
-                            ),
-                            timeseries
-                                .data
-                                .instant
-                                .details
-                                .air_temperature
-                                .as_ref()
-                                .unwrap()
-                        ));
-                    }
-                }
+
+            let from = before.details().air_temperature?;
+            let to = after.details().air_temperature?;
+            let elapsed = (at - before.time).num_seconds() as f64;
+            let interval = (after.time - before.time).num_seconds() as f64;
+            Some(from + (to - from) * elapsed / interval)
+        })
+    }
+
+    /// Details shown for `timeseries`; for the current conditions, with the temperature
+    /// interpolated to `now` when the entry is more than 15 minutes away from it
+    fn displayed_details<'a>(
+        &self,
+        timeseries: &'a Timeseries,
+        duration: DurationType,
+        now: DateTime<Utc>,
+    ) -> Cow<'a, Details> {
+        let details = timeseries.details();
+        if duration != DurationType::Now || (now - timeseries.time).abs() <= Duration::minutes(15) {
+            return Cow::Borrowed(details);
+        }
+
+        match self.interpolate_temperature(now) {
+            Some(temperature) => Cow::Owned(Details {
+                air_temperature: Some(temperature),
+                ..details.clone()
+            }),
+            None => Cow::Borrowed(details),
+        }
+    }
+
+    /// The first `n` hourly entries from the one of the hour of `now`, which met.no gives for
+    /// the next two days or so
+    pub fn next_n_hours(&self, now: DateTime<Utc>, n: u32) -> impl Iterator<Item = &Timeseries> {
+        self.properties
+            .timeseries
+            .iter()
+            .filter(|timeseries| timeseries.data.next_1_hours.is_some())
+            .skip_while(move |timeseries| timeseries.time + Duration::hours(1) <= now)
+            .take(n as usize)
+    }
+
+    /// Warning for the first of the next 6 hours with the precipitation watched by `alert`, e.g.
+    /// `snow forecast at 14:00 (❄️ Light Snow)`
+    pub fn precipitation_alert(
+        &self,
+        alert: PrecipitationAlert,
+        options: &DisplayOptions,
+    ) -> Option<String> {
+        self.next_n_hours(options.now(), ALERT_HOURS).find_map(|timeseries| {
+            let summary = timeseries.summary(DurationType::Today).ok()?;
+            let precipitation = summary.symbol().precipitation_type();
+            if !alert.matches(precipitation) {
+                return None;
             }
-            DurationType::Week => {
-                output.push_str(&format!("this week\n"));
-                // select every timeseries that is this week
-                // for each timeseries, print the day, time and the summary
-                for timeseries in self.properties.timeseries.iter() {
-                    if timeseries.time.day() >= current_time.day()
-                        && timeseries.time.day() <= current_time.day() + 7
-                    {
-                        output.push_str(&format!(
-                            "{} {}: {} {}C\n",
-                            timeseries.time.format("%A"),
-                            timeseries.time.format("%H:%M"),
-                            format_weather_description(
-                                timeseries
-                                    .data
-                                    .next_12_hours
-                                    .as_ref()
-                                    .unwrap()
-                                    .summary
-                                    .symbol_code
-                                    .as_str()
-                            ),
-                            timeseries
-                                .data
-                                .instant
-                                .details
-                                .air_temperature
-                                .as_ref()
-                                .unwrap()
-                        ));
-                    }
-                }
+            Some(format!(
+                "{} forecast at {} ({})",
+                precipitation,
+                entry_time(timeseries, options).format("%H:%M"),
+                describe_weather(&summary.symbol_code, options),
+            ))
+        })
+    }
+
+    /// Warning for the first of the next 6 hours with a severe condition, e.g.
+    /// `⛈️ Heavy Rain and Thunder forecast at 20:00`
+    pub fn severe_weather_alert(&self, options: &DisplayOptions) -> Option<String> {
+        self.next_n_hours(options.now(), ALERT_HOURS).find_map(|timeseries| {
+            let summary = timeseries.summary(DurationType::Today).ok()?;
+            let condition = WeatherCondition::try_from(summary.symbol()).ok()?;
+            condition.is_severe().then(|| {
+                format!(
+                    "{} forecast at {}",
+                    describe_weather(&summary.symbol_code, options),
+                    entry_time(timeseries, options).format("%H:%M"),
+                )
+            })
+        })
+    }
+
+    /// Warning for the first of the next 6 hours colder than `below` or warmer than `above`,
+    /// both in the `--temp-unit`, e.g. `temperature below 0 forecast at 03:00 (-1.2°C)`
+    pub fn temperature_alert(
+        &self,
+        below: Option<f64>,
+        above: Option<f64>,
+        options: &DisplayOptions,
+    ) -> Option<String> {
+        self.next_n_hours(options.now(), ALERT_HOURS).find_map(|timeseries| {
+            let celsius = timeseries.details().air_temperature?;
+            let temperature = convert_temperature(celsius, options.temp_unit);
+            let crossed = match (below, above) {
+                (Some(below), _) if temperature < below => format!("below {}", below),
+                (_, Some(above)) if temperature > above => format!("above {}", above),
+                _ => return None,
+            };
+            Some(format!(
+                "temperature {} forecast at {} ({})",
+                crossed,
+                entry_time(timeseries, options).format("%H:%M"),
+                format_temperature(celsius, options.temp_unit),
+            ))
+        })
+    }
+
+    /// Warning for the first foggy hour in the next 12 hours, e.g. `fog forecast at 06:00`
+    pub fn fog_alert(&self, options: &DisplayOptions) -> Option<String> {
+        let now = options.now();
+        // the entry of the current hour started up to an hour ago
+        let from = now - Duration::hours(1);
+        let fog = self.get_fog_periods(from, now + Duration::hours(FOG_ALERT_HOURS));
+        let first = fog.first()?;
+        Some(format!("fog forecast at {}", entry_time(first, options).format("%H:%M")))
+    }
+
+    /// Entry closest to `t`, before or after it
+    pub fn get_timeseries_at(&self, t: DateTime<Utc>) -> Option<&Timeseries> {
+        self.properties
+            .timeseries
+            .iter()
+            .min_by_key(|timeseries| (timeseries.time - t).abs())
+    }
+
+    /// Entry at exactly `t`, if there is one
+    pub fn get_exact_timeseries(&self, t: DateTime<Utc>) -> Option<&Timeseries> {
+        let timeseries = &self.properties.timeseries;
+        timeseries
+            .binary_search_by_key(&t, |timeseries| timeseries.time)
+            .ok()
+            .map(|index| &timeseries[index])
+    }
+
+    /// Timeseries entries from `from` (inclusive) to `to` (exclusive)
+    pub fn filter_timeseries(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<&Timeseries> {
+        self.get_timeseries_range(from, to).collect()
+    }
+
+    /// Same entries as `filter_timeseries`, borrowed from the forecast without collecting them
+    pub fn get_timeseries_range(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> impl Iterator<Item = &Timeseries> {
+        // met.no lists the entries in time order
+        let timeseries = &self.properties.timeseries;
+        let start = timeseries.partition_point(|timeseries| timeseries.time < from);
+        let end = timeseries.partition_point(|timeseries| timeseries.time < to);
+        timeseries[start..end.max(start)].iter()
+    }
+
+    /// Air temperature of every entry between `from` and `to` that has one
+    pub fn get_hourly_temperature_series(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, f64)> {
+        self.get_timeseries_range(from, to)
+            .filter_map(|timeseries| Some((timeseries.time, timeseries.details().air_temperature?)))
+            .collect()
+    }
+
+    /// Wind speed in m/s and the direction it blows from in degrees, for every entry between
+    /// `from` and `to` that has both
+    pub fn get_hourly_wind_series(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, f64, f64)> {
+        self.get_timeseries_range(from, to)
+            .filter_map(|timeseries| {
+                let details = timeseries.details();
+                Some((timeseries.time, details.wind_speed?, details.wind_from_direction?))
+            })
+            .collect()
+    }
+
+    /// 16-point compass sector the wind most often blows from between `from` and `to`, the
+    /// first one clockwise from north among equally frequent ones
+    pub fn dominant_wind_direction(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Option<&'static str> {
+        let mut counts = [0usize; COMPASS_POINTS.len()];
+        for timeseries in self.get_timeseries_range(from, to) {
+            if let Some(direction) = timeseries.details().wind_from_direction {
+                counts[compass_sector(direction)] += 1;
             }
         }
-        Ok(output)
+        counts
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, count)| **count > 0)
+            .max_by_key(|(_, count)| **count)
+            .map(|(sector, _)| COMPASS_POINTS[sector])
     }
-}
 
-fn format_weather_description(description: &str) -> String {
-    match description {
-        "clearsky_day" => "☀️ Clear Sky (Day)".to_string(),
-        "fair_day" => "🌤️ Fair (Day)".to_string(),
-        "partlycloudy_day" => "⛅ Partly Cloudy (Day)".to_string(),
-        "cloudy" => "☁️ Cloudy".to_string(),
-        "rainshowers_day" => "🌦️ Rain Showers (Day)".to_string(),
-        "rainshowersandthunder_day" => "⛈️ Rain Showers and Thunder (Day)".to_string(),
-        "sleetshowers_day" => "🌨️ Sleet Showers (Day)".to_string(),
-        "snowshowers_day" => "❄️ Snow Showers (Day)".to_string(),
-        "rain" => "🌧️ Rain".to_string(),
-        "heavyrain" => "🌧️ Heavy Rain".to_string(),
-        "heavyrainandthunder" => "⛈️ Heavy Rain and Thunder".to_string(),
-        "sleet" => "🌨️ Sleet".to_string(),
-        "snow" => "❄️ Snow".to_string(),
-        "snowandthunder" => "⛈️ Snow and Thunder".to_string(),
-        "fog" => "🌫️ Fog".to_string(),
-        "sleetshowersandthunder_day" => "⛈️ Sleet Showers and Thunder (Day)".to_string(),
-        "snowshowersandthunder_day" => "⛈️ Snow Showers and Thunder (Day)".to_string(),
-        "rainandthunder" => "⛈️ Rain and Thunder".to_string(),
-        "sleetandthunder" => "⛈️ Sleet and Thunder".to_string(),
-        "lightrainshowersandthunder_day" => "⛈️ Light Rain Showers and Thunder (Day)".to_string(),
-        "heavyrainshowersandthunder_day" => "⛈️ Heavy Rain Showers and Thunder (Day)".to_string(),
-        "lightssleetshowersandthunder_day" => "⛈️ Light Sleet Showers and Thunder (Day)".to_string(),
-        "heavysleetshowersandthunder_day" => "⛈️ Heavy Sleet Showers and Thunder (Day)".to_string(),
-        "lightssnowshowersandthunder_day" => "⛈️ Light Snow Showers and Thunder (Day)".to_string(),
-        "heavysnowshowersandthunder_day" => "⛈️ Heavy Snow Showers and Thunder (Day)".to_string(),
-        "lightrainandthunder" => "⛈️ Light Rain and Thunder".to_string(),
-        "lightsleetandthunder" => "⛈️ Light Sleet and Thunder".to_string(),
-        "heavysleetandthunder" => "⛈️ Heavy Sleet and Thunder".to_string(),
-        "lightsnowandthunder" => "⛈️ Light Snow and Thunder".to_string(),
-        "heavysnowandthunder" => "⛈️ Heavy Snow and Thunder".to_string(),
-        "lightrainshowers_day" => "🌦️ Light Rain Showers (Day)".to_string(),
-        "heavyrainshowers_day" => "🌦️ Heavy Rain Showers (Day)".to_string(),
-        "lightsleetshowers_day" => "🌦️ Light Sleet Showers (Day)".to_string(),
-        "heavysleetshowers_day" => "🌦️ Heavy Sleet Showers (Day)".to_string(),
-        "lightsnowshowers_day" => "🌦️ Light Snow Showers (Day)".to_string(),
-        "heavysnowshowers_day" => "🌦️ Heavy Snow Showers (Day)".to_string(),
-        "lightrain" => "🌧️ Light Rain".to_string(),
-        "lightsleet" => "🌨️ Light Sleet".to_string(),
-        "heavysleet" => "🌨️ Heavy Sleet".to_string(),
-        "lightsnow" => "❄️ Light Snow".to_string(),
-        "heavysnow" => "❄️ Heavy Snow".to_string(),
-        "clearsky_night" => "🌙 Clear Sky (Night)".to_string(),
-        "fair_night" => "🌙 Fair (Night)".to_string(),
-        "partlycloudy_night" => "🌙☁️P Partly Cloudy (Night)".to_string(),
-        "rainshowers_night" => "🌦️ Rain Showers (Night)".to_string(),
-        "rainshowersandthunder_night" => "⛈️ Rain Showers and Thunder (Night)".to_string(),
-        "sleetshowers_night" => "🌨️ Sleet Showers (Night)".to_string(),
-        "snowshowers_night" => "❄️ Snow Showers (Night)".to_string(),
-        "sleetshowersandthunder_night" => "⛈️ Sleet Showers and Thunder (Night)".to_string(),
-        "snowshowersandthunder_night" => "⛈️ Snow Showers and Thunder (Night)".to_string(),
-        "lightrainshowersandthunder_night" => {
-            "⛈️ Light Rain Showers and Thunder (Night)".to_string()
-        }
-        "heavyrainshowersandthunder_night" => {
-            "⛈️ Heavy Rain Showers and Thunder (Night)".to_string()
-        }
-        "lightssleetshowersandthunder_night" => {
-            "⛈️ Light Sleet Showers and Thunder (Night)".to_string()
-        }
-        "heavysleetshowersandthunder_night" => {
-            "⛈️ Heavy Sleet Showers and Thunder (Night)".to_string()
-        }
-        "lightssnowshowersandthunder_night" => {
-            "⛈️ Light Snow Showers and Thunder (Night)".to_string()
-        }
-        "heavysnowshowersandthunder_night" => {
-            "⛈️ Heavy Snow Showers and Thunder (Night)".to_string()
-        }
-        "lightrainshowers_night" => "🌦️ Light Rain Showers (Night)".to_string(),
-        "heavyrainshowers_night" => "🌦️ Heavy Rain Showers (Night)".to_string(),
-        "lightsleetshowers_night" => "🌦️ Light Sleet Showers (Night)".to_string(),
-        "heavysleetshowers_night" => "🌦️ Heavy Sleet Showers (Night)".to_string(),
-        "lightsnowshowers_night" => "🌦️ Light Snow Showers (Night)".to_string(),
-        "heavysnowshowers_night" => "🌦️ Heavy Snow Showers (Night)".to_string(),
-        "clearsky_polartwilight" => "🌌 Clear Sky (Polar Twilight)".to_string(),
-        "fair_polartwilight" => "🌌 Fair (Polar Twilight)".to_string(),
-        "partlycloudy_polartwilight" => "🌌 Partly Cloudy (Polar Twilight)".to_string(),
-        "rainshowers_polartwilight" => "🌌 Rain Showers (Polar Twilight)".to_string(),
-        "rainshowersandthunder_polartwilight" => {
-            "🌌 Rain Showers and Thunder (Polar Twilight)".to_string()
-        }
-        "sleetshowers_polartwilight" => "🌌 Sleet Showers (Polar Twilight)".to_string(),
-        "snowshowers_polartwilight" => "🌌 Snow Showers (Polar Twilight)".to_string(),
-        "sleetshowersandthunder_polartwilight" => {
-            "🌌 Sleet Showers and Thunder (Polar Twilight)".to_string()
-        }
-        "snowshowersandthunder_polartwilight" => {
-            "🌌 Snow Showers and Thunder (Polar Twilight)".to_string()
-        }
-        "lightrainshowersandthunder_polartwilight" => {
-            "🌌 Light Rain Showers and Thunder (Polar Twilight)".to_string()
-        }
-        "heavyrainshowersandthunder_polartwilight" => {
-            "🌌 Heavy Rain Showers and Thunder (Polar Twilight)".to_string()
-        }
-        "lightssleetshowersandthunder_polartwilight" => {
-            "🌌 Light Sleet Showers and Thunder (Polar Twilight)".to_string()
-        }
-        "heavysleetshowersandthunder_polartwilight" => {
-            "🌌 Heavy Sleet Showers and Thunder (Polar Twilight)".to_string()
-        }
-        "lightssnowshowersandthunder_polartwilight" => {
-            "🌌 Light Snow Showers and Thunder (Polar Twilight)".to_string()
-        }
-        "heavysnowshowersandthunder_polartwilight" => {
-            "🌌 Heavy Snow Showers and Thunder (Polar Twilight)".to_string()
-        }
-        "lightrainshowers_polartwilight" => "🌌 Light Rain Showers (Polar Twilight)".to_string(),
-        "heavyrainshowers_polartwilight" => "🌌 Heavy Rain Showers (Polar Twilight)".to_string(),
-        "lightsleetshowers_polartwilight" => "🌌 Light Sleet Showers (Polar Twilight)".to_string(),
-        "heavysleetshowers_polartwilight" => "🌌 Heavy Sleet Showers (Polar Twilight)".to_string(),
-        "lightsnowshowers_polartwilight" => "🌌 Light Snow Showers (Polar Twilight)".to_string(),
-        "heavysnowshowers_polartwilight" => "🌌 Heavy Snow Showers (Polar Twilight)".to_string(),
-        _ => description.to_string(), // Default to the original description if not found
+    /// Mean air temperature of the entries between `from` and `to`
+    pub fn average_temperature(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Option<f64> {
+        mean(
+            self.get_hourly_temperature_series(from, to)
+                .into_iter()
+                .map(|(_, temperature)| temperature),
+        )
+    }
+
+    /// Mean wind speed of the entries between `from` and `to`, in m/s
+    pub fn average_wind_speed(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Option<f64> {
+        mean(
+            self.get_timeseries_range(from, to)
+                .filter_map(|timeseries| timeseries.details().wind_speed),
+        )
+    }
+
+    /// Entry with the highest air temperature between `from` and `to`
+    pub fn get_warmest_period(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Option<&Timeseries> {
+        self.get_timeseries_range(from, to)
+            .filter_map(|timeseries| Some((timeseries, timeseries.details().air_temperature?)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(timeseries, _)| timeseries)
+    }
+
+    /// Entry with the lowest air temperature between `from` and `to`
+    pub fn get_coldest_period(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Option<&Timeseries> {
+        self.get_timeseries_range(from, to)
+            .filter_map(|timeseries| Some((timeseries, timeseries.details().air_temperature?)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(timeseries, _)| timeseries)
+    }
+
+    /// Entry with the highest wind speed between `from` and `to`
+    pub fn get_windiest_period(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Option<&Timeseries> {
+        self.get_timeseries_range(from, to)
+            .filter_map(|timeseries| Some((timeseries, timeseries.details().wind_speed?)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(timeseries, _)| timeseries)
+    }
+
+    /// Entries between `from` and `to` whose hour is forecast to be foggy
+    pub fn get_fog_periods(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<&Timeseries> {
+        self.get_timeseries_range(from, to)
+            .filter(|timeseries| {
+                timeseries
+                    .summary(DurationType::Today)
+                    .is_ok_and(|summary| summary.symbol().base_condition() == "fog")
+            })
+            .collect()
+    }
+
+    /// Longest run of consecutive clear or fair hours between `from` and `to`, as the start of
+    /// its first hour and the end of its last one
+    pub fn get_clear_weather_window(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let mut longest: Option<(DateTime<Utc>, DateTime<Utc>)> = None;
+        let mut current: Option<(DateTime<Utc>, DateTime<Utc>)> = None;
+
+        for timeseries in self.get_timeseries_range(from, to) {
+            // only the hourly summary tells the condition of a single hour
+            let clear = timeseries.summary(DurationType::Today).is_ok_and(|summary| {
+                matches!(summary.symbol().base_condition(), "clearsky" | "fair")
+            });
+            if !clear {
+                current = None;
+                continue;
+            }
+
+            let end = timeseries.time + Duration::hours(1);
+            let window = match current {
+                Some((start, _)) => (start, end),
+                None => (timeseries.time, end),
+            };
+            current = Some(window);
+            if longest.is_none_or(|(start, end)| window.1 - window.0 > end - start) {
+                longest = Some(window);
+            }
+        }
+        longest
+    }
+
+    /// Rough UV index at solar noon of the day of `now` at latitude `lat`, since met.no gives
+    /// no UV index in this API: a clear-sky model from the sun's noon elevation for the season,
+    /// scaled down by the cloud cover at `now`
+    pub fn estimated_uv_index(&self, lat: f64, now: DateTime<Utc>) -> Option<f64> {
+        let timeseries = self.lazy_timeseries(DurationType::Now, now, None).next()?;
+        let cloud_cover = timeseries.details().cloud_area_fraction?;
+
+        let day_of_year = timeseries.time.ordinal() as f64;
+        let declination = 23.44 * (2.0 * PI * (284.0 + day_of_year) / 365.0).sin();
+        let zenith = (lat - declination).abs().to_radians();
+        let clear_sky = CLEAR_SKY_UV_INDEX * zenith.cos().max(0.0).powf(2.42);
+        Some(clear_sky * (1.0 - cloud_cover / 100.0))
+    }
+
+    /// WHO exposure category of `estimated_uv_index`
+    pub fn uv_warning_level(&self, lat: f64, now: DateTime<Utc>) -> &'static str {
+        match self.estimated_uv_index(lat, now) {
+            None => "Unknown",
+            Some(index) if index < 3.0 => "Low",
+            Some(index) if index < 6.0 => "Moderate",
+            Some(index) if index < 8.0 => "High",
+            Some(index) if index < 11.0 => "Very High",
+            Some(_) => "Extreme",
+        }
+    }
+
+    /// One row per timeseries entry covered by `duration`, with the columns of `csv_header`
+    pub fn csv_rows(
+        &self,
+        duration: DurationType,
+        now: DateTime<Utc>,
+        columns: &[ColumnField],
+    ) -> Vec<String> {
+        self.lazy_timeseries(duration, now, None)
+            .map(|timeseries| {
+                let details = timeseries.details();
+                let mut row = vec![timeseries.time.to_rfc3339()];
+                row.extend(selected_columns(columns).iter().map(|column| match column {
+                    ColumnField::Weather => timeseries
+                        .summary(duration)
+                        .map(|summary| summary.symbol_code.clone())
+                        .unwrap_or_default(),
+                    ColumnField::Temperature => format_csv_value(details.air_temperature),
+                    ColumnField::WindSpeed => format_csv_value(details.wind_speed),
+                    ColumnField::WindDirection => format_csv_value(details.wind_from_direction),
+                    ColumnField::Humidity => format_csv_value(details.relative_humidity),
+                    ColumnField::CloudCover => format_csv_value(details.cloud_area_fraction),
+                    ColumnField::Pressure => format_csv_value(details.air_pressure_at_sea_level),
+                    ColumnField::Precipitation => {
+                        format_csv_value(timeseries.precipitation_amount(duration))
+                    }
+                }));
+                row.join(",")
+            })
+            .collect()
+    }
+
+    fn display_complete(
+        &self,
+        duration: DurationType,
+        location_name: String,
+        options: &DisplayOptions,
+    ) -> Result<String, WeatherError> {
+        let current_time = options.now();
+        let header = view_header(duration, &location_name, options);
+        let mut output = format!("{}\n", fit_width(header.trim_end(), options));
+        // --show-units already printed the units
+        if !options.show_units {
+            let updated_at = display_time(self.properties.meta.updated_at, options);
+            output.push_str(&format!(
+                "Updated: {}\nUnits:\n{}",
+                updated_at.format("%Y-%m-%d %H:%M"),
+                displayed_units(&self.properties.meta.units, options),
+            ));
+        }
+        if let Some(&lat) = self.geometry.coordinates.get(1) {
+            if let Some(index) = self.estimated_uv_index(lat, current_time) {
+                output.push_str(&format!(
+                    "Estimated UV index: {:.0} ({})\n",
+                    index,
+                    self.uv_warning_level(lat, current_time)
+                ));
+            }
+        }
+        let mut day = None;
+        let mut derived = false;
+        for timeseries in self.lazy_timeseries(duration, current_time, options.timezone) {
+            if let Some(heading) = self.day_heading(duration, timeseries, &mut day, options) {
+                output.push_str(&format!("{}\n", heading));
+            }
+            let details = self.displayed_details(timeseries, duration, current_time);
+            let dew_point = details.dew_point_temperature();
+            let absolute_humidity = details.absolute_humidity_g_per_m3();
+            derived |= dew_point.is_some() || absolute_humidity.is_some();
+            let measurements = format!(
+                " {}, wind {} from {}°, humidity {}%{}{}, cloud cover {}%, pressure {}",
+                format_details_temperature(&details, options)?,
+                format_speed(
+                    details.wind_speed.ok_or(WeatherError::MissingData)?,
+                    options.speed_unit,
+                ),
+                details.wind_from_direction.ok_or(WeatherError::MissingData)?,
+                details.relative_humidity.ok_or(WeatherError::MissingData)?,
+                absolute_humidity
+                    .map(|grams| format!(" ({:.1} g/m³*)", grams))
+                    .unwrap_or_default(),
+                format_dew_point(dew_point, options),
+                details.cloud_area_fraction.ok_or(WeatherError::MissingData)?,
+                format_pressure(
+                    details.air_pressure_at_sea_level.ok_or(WeatherError::MissingData)?,
+                    options.pressure_unit,
+                ),
+            );
+            output.push_str(&format_line(
+                &format_time_prefix(duration, entry_time(timeseries, options)),
+                &timeseries.summary(duration)?.symbol_code,
+                &measurements,
+                options,
+            ));
+            output.push('\n');
+        }
+        if let Some((from, to)) = multi_day_bounds(duration, current_time) {
+            let wind: Vec<(f64, f64)> = self
+                .get_hourly_wind_series(from, to)
+                .into_iter()
+                .map(|(_, speed, direction)| (convert_speed(speed, options.speed_unit), direction))
+                .collect();
+            output.push_str(&charts::wind_rose_ascii(
+                &wind,
+                speed_unit_symbol(options.speed_unit),
+                options.width.unwrap_or(80),
+            ));
+        }
+        let bounds =
+            day_bounds(duration, current_time).or_else(|| multi_day_bounds(duration, current_time));
+        if let Some((start, end)) =
+            bounds.and_then(|(from, to)| self.get_clear_weather_window(from, to))
+        {
+            let format = entry_time_format(duration);
+            output.push_str(&format!(
+                "Longest clear spell: {} to {}\n",
+                display_time(start, options).format(format),
+                display_time(end, options).format(format),
+            ));
+        }
+        if derived {
+            output.push_str(DERIVED_VALUES_NOTE);
+        }
+        Ok(output)
+    }
+
+    fn display_detailed(
+        &self,
+        duration: DurationType,
+        location_name: String,
+        options: &DisplayOptions,
+    ) -> Result<String, WeatherError> {
+        let current_time = options.now();
+        let header = view_header(duration, &location_name, options);
+        let mut output = format!("{}\n", fit_width(header.trim_end(), options));
+        let mut day = None;
+        let mut derived = false;
+        for timeseries in self.lazy_timeseries(duration, current_time, options.timezone) {
+            if let Some(heading) = self.day_heading(duration, timeseries, &mut day, options) {
+                output.push_str(&format!("{}\n", heading));
+            }
+            if !options.columns.is_empty() {
+                output.push_str(&format!(
+                    "{}{}\n",
+                    format_time_prefix(duration, entry_time(timeseries, options)),
+                    format_columns(timeseries, duration, options)?,
+                ));
+                continue;
+            }
+
+            let details = self.displayed_details(timeseries, duration, current_time);
+            let dew_point = details.dew_point_temperature();
+            derived |= dew_point.is_some();
+            let mut measurements = format!(
+                " {}, wind {}, humidity {}%{}, pressure {}",
+                format_details_temperature(&details, options)?,
+                format_speed(
+                    details.wind_speed.ok_or(WeatherError::MissingData)?,
+                    options.speed_unit,
+                ),
+                details.relative_humidity.ok_or(WeatherError::MissingData)?,
+                format_dew_point(dew_point, options),
+                format_pressure(
+                    details.air_pressure_at_sea_level.ok_or(WeatherError::MissingData)?,
+                    options.pressure_unit,
+                ),
+            );
+            if let Some(amount) = timeseries.precipitation_amount(duration) {
+                measurements.push_str(&format!(
+                    ", chance of precipitation {:.0}%",
+                    precipitation_probability(amount) * 100.0
+                ));
+            }
+            let next_hour_details = match duration {
+                DurationType::NextHour => timeseries.data.next_1_hours.as_ref(),
+                _ => None,
+            }
+            .and_then(|next| next.details.as_ref());
+            if let Some(details) = next_hour_details {
+                measurements.push_str(&format!(
+                    "; over the hour: {}",
+                    format_available_details(details, options)
+                ));
+            }
+            output.push_str(&format_line(
+                &format_time_prefix(duration, entry_time(timeseries, options)),
+                &timeseries.summary(duration)?.symbol_code,
+                &measurements,
+                options,
+            ));
+            output.push('\n');
+        }
+
+        if let Some((from, to)) = day_bounds(duration, current_time) {
+            let precipitation: Vec<(DateTime<FixedOffset>, f64)> = self
+                .get_timeseries_range(from, to)
+                .filter_map(|timeseries| {
+                    let amount = timeseries.precipitation_amount(duration)?;
+                    Some((entry_time(timeseries, options), amount))
+                })
+                .collect();
+            let width = options.width.unwrap_or(precipitation.len());
+            output.push_str(&charts::precipitation_bar_chart(&precipitation, width));
+
+            let extremes = [
+                ("Warmest", self.get_warmest_period(from, to)),
+                ("Coldest", self.get_coldest_period(from, to)),
+            ];
+            for (label, timeseries) in extremes {
+                if let Some(timeseries) = timeseries {
+                    output.push_str(&format!(
+                        "{}: {} — {}\n",
+                        label,
+                        entry_time(timeseries, options).format("%H:%M"),
+                        format_details_temperature(timeseries.details(), options)?,
+                    ));
+                }
+            }
+
+            let windiest = match duration {
+                DurationType::Today => self.get_windiest_period(from, to),
+                _ => None,
+            };
+            if let Some(timeseries) = windiest {
+                let details = timeseries.details();
+                let direction = details.wind_from_direction.ok_or(WeatherError::MissingData)?;
+                output.push_str(&format!(
+                    "Windiest: {} — {} {}\n",
+                    entry_time(timeseries, options).format("%H:%M"),
+                    format_speed(
+                        details.wind_speed.ok_or(WeatherError::MissingData)?,
+                        options.speed_unit,
+                    ),
+                    compass_direction(direction),
+                ));
+            }
+            if let Some(direction) = self.dominant_wind_direction(from, to) {
+                output.push_str(&format!("Prevailing wind: {}\n", direction));
+            }
+            if let (Some(temperature), Some(wind_speed)) =
+                (self.average_temperature(from, to), self.average_wind_speed(from, to))
+            {
+                output.push_str(&format!(
+                    "Average: {}, wind {}\n",
+                    format_temperature(temperature, options.temp_unit),
+                    format_speed(wind_speed, options.speed_unit),
+                ));
+            }
+        }
+        if derived {
+            output.push_str(DERIVED_VALUES_NOTE);
+        }
+        Ok(output)
+    }
+
+    /// Forecast as an aligned table, no wider than `--width`
+    fn display_table(
+        &self,
+        duration: DurationType,
+        location_name: String,
+        options: &DisplayOptions,
+    ) -> Result<String, WeatherError> {
+        // cells are measured to align the columns, which escape codes would throw off
+        let options = &DisplayOptions {
+            palette: None,
+            ..options.clone()
+        };
+        let current_time = options.now();
+        let time_format = entry_time_format(duration);
+
+        let rows = self
+            .lazy_timeseries(duration, current_time, options.timezone)
+            .map(|timeseries| {
+                let details = self.displayed_details(timeseries, duration, current_time);
+                Ok(vec![
+                    entry_time(timeseries, options).format(time_format).to_string(),
+                    describe_weather(&timeseries.summary(duration)?.symbol_code, options),
+                    format_details_temperature(&details, options)?,
+                    format_speed(
+                        details.wind_speed.ok_or(WeatherError::MissingData)?,
+                        options.speed_unit,
+                    ),
+                    format!("{}%", details.relative_humidity.ok_or(WeatherError::MissingData)?),
+                ])
+            })
+            .collect::<Result<Vec<_>, WeatherError>>()?;
+
+        let header = view_header(duration, &location_name, options);
+        let header = fit_width(header.trim_end(), options);
+        let table = table::render_table(
+            &["Time", "Weather", "Temperature", "Wind", "Humidity"],
+            &rows,
+            options.width,
+        );
+        Ok(format!("{}\n{}", header, table.trim_end()))
+    }
+
+    /// Current conditions on a single line, without header
+    pub(crate) fn display_minimal(&self, options: &DisplayOptions) -> Result<String, WeatherError> {
+        let now = options.now();
+        let timeseries = self.next_n_hours(now, 1).next().ok_or(WeatherError::MissingData)?;
+
+        Ok(format!(
+            "{} {}",
+            describe_weather(&timeseries.summary(DurationType::Now)?.symbol_code, options),
+            format_details_temperature(
+                &self.displayed_details(timeseries, DurationType::Now, now),
+                options
+            )?,
+        ))
+    }
+
+    /// One minimal line per hour for `--hours-ahead`, each prefixed by its time
+    fn display_minimal_hours(
+        &self,
+        hours: u32,
+        options: &DisplayOptions,
+    ) -> Result<String, WeatherError> {
+        let lines = self
+            .next_n_hours(options.now(), hours)
+            .map(|timeseries| {
+                Ok(format!(
+                    "{} {} {}",
+                    entry_time(timeseries, options).format("%H:%M"),
+                    describe_weather(&timeseries.summary(DurationType::Now)?.symbol_code, options),
+                    format_details_temperature(timeseries.details(), options)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, WeatherError>>()?;
+        Ok(lines.join("\n"))
+    }
+
+    /// tmux status-line segment such as `#[fg=colour33] ⛅ 14°C`, with the temperature rounded
+    /// to keep it short
+    fn display_tmux(&self, options: &DisplayOptions) -> Result<String, WeatherError> {
+        let now = options.now();
+        let timeseries = self.next_n_hours(now, 1).next().ok_or(WeatherError::MissingData)?;
+        let celsius = self
+            .displayed_details(timeseries, DurationType::Now, now)
+            .air_temperature
+            .ok_or(WeatherError::MissingData)?;
+        let temperature = format_rounded_temperature(celsius, options.temp_unit);
+
+        let mut segment = format!("#[fg=colour{}]", color::tmux_colour(celsius));
+        if !options.no_emoji {
+            let description =
+                format_weather_description(&timeseries.summary(DurationType::Now)?.symbol_code);
+            if let Some(emoji) = description.split(' ').next() {
+                segment.push(' ');
+                segment.push_str(emoji);
+            }
+        }
+        segment.push(' ');
+        segment.push_str(&temperature);
+        Ok(segment)
+    }
+
+    /// Xmobar line such as `⛅ Cloudy <fc=#5fd75f>14°C</fc>`
+    fn display_xmobar(&self, options: &DisplayOptions) -> Result<String, WeatherError> {
+        let now = options.now();
+        let timeseries = self.next_n_hours(now, 1).next().ok_or(WeatherError::MissingData)?;
+        let celsius = self
+            .displayed_details(timeseries, DurationType::Now, now)
+            .air_temperature
+            .ok_or(WeatherError::MissingData)?;
+        let summary = timeseries.summary(DurationType::Now)?;
+
+        let mut line = String::new();
+        if !options.no_emoji {
+            let description = format_weather_description(&summary.symbol_code);
+            if let Some(emoji) = description.split(' ').next() {
+                line.push_str(emoji);
+                line.push(' ');
+            }
+        }
+        Ok(format!(
+            "{}{} <fc={}>{}</fc>",
+            line,
+            summary.symbol().short_description(),
+            color::xmobar_color(celsius),
+            format_rounded_temperature(celsius, options.temp_unit),
+        ))
+    }
+
+    fn display_compact(
+        &self,
+        duration: DurationType,
+        location_name: String,
+        options: &DisplayOptions,
+    ) -> Result<String, WeatherError> {
+        let mut output = Vec::new();
+        self.streaming_display(duration, &location_name, options, &mut output)?;
+        Ok(String::from_utf8(output).expect("forecast output is valid UTF-8"))
+    }
+}
+
+/// Legend of the unit of every field, as reported by met.no
+fn format_units_legend(units: &Units, options: &DisplayOptions) -> String {
+    format!("Units:\n{}", displayed_units(units, options))
+}
+
+/// `units` with the units temperatures and pressures are converted to before display, since
+/// the API unit would be wrong for them
+fn displayed_units(units: &Units, options: &DisplayOptions) -> Units {
+    let air_temperature = match options.temp_unit {
+        TempUnit::Celsius => units.air_temperature.clone(),
+        TempUnit::Fahrenheit => Some("fahrenheit".to_string()),
+    };
+    let air_pressure_at_sea_level = match options.pressure_unit {
+        PressureUnit::Hectopascal => units.air_pressure_at_sea_level.clone(),
+        PressureUnit::Millibar => Some("mbar".to_string()),
+        PressureUnit::InchesOfMercury => Some("inHg".to_string()),
+    };
+    let wind_speed = match options.speed_unit {
+        SpeedUnit::MetersPerSecond => units.wind_speed.clone(),
+        unit => Some(speed_unit_symbol(unit).to_string()),
+    };
+    Units {
+        air_temperature,
+        air_pressure_at_sea_level,
+        wind_speed,
+        ..units.clone()
+    }
+}
+
+/// Width of the terminal stdout is attached to, or 80 columns when it is not a terminal
+fn terminal_width() -> usize {
+    terminal_size::terminal_size()
+        .map(|(terminal_size::Width(width), _)| usize::from(width))
+        .unwrap_or(80)
+}
+
+/// `text` truncated with `…` when it is wider than `--width`
+fn fit_width(text: &str, options: &DisplayOptions) -> String {
+    match options.width {
+        Some(width) => table::truncate(text, width),
+        None => text.to_string(),
+    }
+}
+
+/// Forecast line made of `prefix`, the description of `symbol_code` and `suffix`, shortening
+/// the description so that the line fits `--width`
+fn format_line(prefix: &str, symbol_code: &str, suffix: &str, options: &DisplayOptions) -> String {
+    let description = describe_weather(symbol_code, options);
+    let description = match options.width {
+        Some(width) => {
+            let available =
+                width.saturating_sub(color::visible_width(prefix) + color::visible_width(suffix));
+            table::truncate(&description, available)
+        }
+        None => description,
+    };
+    let description = match options.palette {
+        Some(palette) => color::paint(&description, palette.condition(SymbolCode(symbol_code))),
+        None => description,
+    };
+    format!("{}{}{}", prefix, description, suffix)
+}
+
+/// The `--columns` of a detailed line, in the order they were given
+fn format_columns(
+    timeseries: &Timeseries,
+    duration: DurationType,
+    options: &DisplayOptions,
+) -> Result<String, WeatherError> {
+    let details = timeseries.details();
+    let value = |value: Option<f64>| value.ok_or(WeatherError::MissingData);
+
+    let fields = options
+        .columns
+        .iter()
+        .map(|column| {
+            Ok(match column {
+                ColumnField::Weather => {
+                    describe_weather(&timeseries.summary(duration)?.symbol_code, options)
+                }
+                ColumnField::Temperature => format_details_temperature(details, options)?,
+                ColumnField::WindSpeed => {
+                    format!("wind {}", format_speed(value(details.wind_speed)?, options.speed_unit))
+                }
+                ColumnField::WindDirection => {
+                    format!("from {}°", value(details.wind_from_direction)?)
+                }
+                ColumnField::Humidity => format!("humidity {}%", value(details.relative_humidity)?),
+                ColumnField::CloudCover => {
+                    format!("cloud cover {}%", value(details.cloud_area_fraction)?)
+                }
+                ColumnField::Pressure => {
+                    let pressure = value(details.air_pressure_at_sea_level)?;
+                    format!("pressure {}", format_pressure(pressure, options.pressure_unit))
+                }
+                ColumnField::Precipitation => format!(
+                    "precipitation {} mm",
+                    value(timeseries.precipitation_amount(duration))?
+                ),
+            })
+        })
+        .collect::<Result<Vec<_>, WeatherError>>()?;
+    Ok(fields.join(", "))
+}
+
+/// Description of a symbol code, without its leading emoji with `--no-emoji`
+pub(crate) fn describe_weather(symbol_code: &str, options: &DisplayOptions) -> String {
+    let description = format_weather_description(symbol_code);
+    match description.split_once(' ') {
+        Some((_, text)) if options.no_emoji => text.to_string(),
+        _ => description,
+    }
+}
+
+/// Air temperature of `details`, followed by the perceived temperature with `--feels-like`
+fn format_details_temperature(
+    details: &Details,
+    options: &DisplayOptions,
+) -> Result<String, WeatherError> {
+    let paint = |celsius: f64| {
+        let temperature = format_temperature(celsius, options.temp_unit);
+        match options.palette {
+            Some(palette) => color::paint(&temperature, palette.temperature(celsius)),
+            None => temperature,
+        }
+    };
+    let temperature = paint(details.air_temperature.ok_or(WeatherError::MissingData)?);
+
+    match details.feels_like_temperature() {
+        Some(feels_like) if options.feels_like => {
+            Ok(format!("{} (feels like {})", temperature, paint(feels_like)))
+        }
+        _ => Ok(temperature),
+    }
+}
+
+/// Footnote of the values marked with `*`, which met.no does not give
+const DERIVED_VALUES_NOTE: &str =
+    "* derived from the temperature and humidity, not given by met.no\n";
+
+/// `, dew point …*` when it is known
+fn format_dew_point(dew_point: Option<f64>, options: &DisplayOptions) -> String {
+    match dew_point {
+        Some(celsius) => format!(", dew point {}*", format_temperature(celsius, options.temp_unit)),
+        None => String::new(),
+    }
+}
+
+/// Every field given in `details`, e.g. `precipitation 0.4 mm, wind 3.1 m/s`
+fn format_available_details(details: &Details, options: &DisplayOptions) -> String {
+    let fields = [
+        details.air_temperature.map(|celsius| {
+            format!("temperature {}", format_temperature(celsius, options.temp_unit))
+        }),
+        details
+            .precipitation_amount
+            .map(|amount| format!("precipitation {} mm", amount)),
+        details
+            .wind_speed
+            .map(|speed| format!("wind {}", format_speed(speed, options.speed_unit))),
+        details
+            .wind_from_direction
+            .map(|degrees| format!("wind from {}°", degrees)),
+        details
+            .relative_humidity
+            .map(|humidity| format!("humidity {}%", humidity)),
+        details
+            .cloud_area_fraction
+            .map(|cover| format!("cloud cover {}%", cover)),
+        details
+            .air_pressure_at_sea_level
+            .map(|hpa| format!("pressure {}", format_pressure(hpa, options.pressure_unit))),
+    ];
+    fields.into_iter().flatten().collect::<Vec<_>>().join(", ")
+}
+
+pub(crate) fn format_temperature(celsius: f64, unit: TempUnit) -> String {
+    match unit {
+        TempUnit::Celsius => format!("{:.1}°C", celsius),
+        TempUnit::Fahrenheit => format!("{:.1}°F", convert_temperature(celsius, unit)),
+    }
+}
+
+/// `celsius` degrees in `unit`
+pub fn convert_temperature(celsius: f64, unit: TempUnit) -> f64 {
+    match unit {
+        TempUnit::Celsius => celsius,
+        TempUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+    }
+}
+
+/// Temperature without decimals, for status bars
+fn format_rounded_temperature(celsius: f64, unit: TempUnit) -> String {
+    match unit {
+        TempUnit::Celsius => format!("{:.0}°C", celsius),
+        TempUnit::Fahrenheit => format!("{:.0}°F", celsius * 9.0 / 5.0 + 32.0),
+    }
+}
+
+/// Hours ahead watched by the alerts
+const ALERT_HOURS: u32 = 6;
+/// Hours ahead watched by `--alert-fog`
+const FOG_ALERT_HOURS: i64 = 12;
+
+/// UV index with the sun straight overhead and a clear sky
+const CLEAR_SKY_UV_INDEX: f64 = 12.5;
+
+/// Amount in mm below which precipitation is treated as not happening
+const PRECIPITATION_THRESHOLD: f64 = 0.1;
+/// Amount in mm from which precipitation is treated as certain
+const PRECIPITATION_CERTAIN: f64 = 5.0;
+
+/// Chance of precipitation from 0 to 1, rising linearly with the forecast `amount` in mm
+fn precipitation_probability(amount: f64) -> f64 {
+    match amount < PRECIPITATION_THRESHOLD {
+        true => 0.0,
+        false => (amount / PRECIPITATION_CERTAIN).min(1.0),
+    }
+}
+
+const HECTOPASCALS_PER_INCH_OF_MERCURY: f64 = 33.8639;
+
+/// `hpa` hectopascals in `unit`
+pub fn convert_pressure(hpa: f64, unit: PressureUnit) -> f64 {
+    match unit {
+        PressureUnit::Hectopascal | PressureUnit::Millibar => hpa,
+        PressureUnit::InchesOfMercury => hpa / HECTOPASCALS_PER_INCH_OF_MERCURY,
+    }
+}
+
+/// Meters per second in a mile per hour
+const METERS_PER_SECOND_PER_MILE_PER_HOUR: f64 = 0.44704;
+/// Meters per second in a knot
+const METERS_PER_SECOND_PER_KNOT: f64 = 1852.0 / 3600.0;
+
+/// `meters_per_second` in `unit`
+pub fn convert_speed(meters_per_second: f64, unit: SpeedUnit) -> f64 {
+    match unit {
+        SpeedUnit::MetersPerSecond => meters_per_second,
+        SpeedUnit::KilometersPerHour => meters_per_second * 3.6,
+        SpeedUnit::MilesPerHour => meters_per_second / METERS_PER_SECOND_PER_MILE_PER_HOUR,
+        SpeedUnit::Knots => meters_per_second / METERS_PER_SECOND_PER_KNOT,
+    }
+}
+
+pub(crate) fn format_speed(meters_per_second: f64, unit: SpeedUnit) -> String {
+    format!(
+        "{:.1} {}",
+        convert_speed(meters_per_second, unit),
+        speed_unit_symbol(unit)
+    )
+}
+
+fn speed_unit_symbol(unit: SpeedUnit) -> &'static str {
+    match unit {
+        SpeedUnit::MetersPerSecond => "m/s",
+        SpeedUnit::KilometersPerHour => "km/h",
+        SpeedUnit::MilesPerHour => "mph",
+        SpeedUnit::Knots => "kn",
+    }
+}
+
+fn format_pressure(hpa: f64, unit: PressureUnit) -> String {
+    match unit {
+        PressureUnit::Hectopascal => format!("{:.1} hPa", hpa),
+        PressureUnit::Millibar => format!("{:.1} mbar", hpa),
+        PressureUnit::InchesOfMercury => format!("{:.2} inHg", convert_pressure(hpa, unit)),
+    }
+}
+
+fn format_csv_value(value: Option<f64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// `time` in the `--timezone`
+pub(crate) fn display_time(time: DateTime<Utc>, options: &DisplayOptions) -> DateTime<FixedOffset> {
+    match options.timezone {
+        Some(tz) => time.with_timezone(&tz).fixed_offset(),
+        None => time.fixed_offset(),
+    }
+}
+
+/// Date of `time` in `timezone`, or its UTC date when it is `None`
+fn local_date(time: DateTime<Utc>, timezone: Option<Tz>) -> NaiveDate {
+    match timezone {
+        Some(tz) => time.with_timezone(&tz).date_naive(),
+        None => time.date_naive(),
+    }
+}
+
+/// Start of `date` and of the day after it in `timezone` (UTC when `None`), as UTC times
+pub(crate) fn local_day_bounds(
+    date: NaiveDate,
+    timezone: Option<Tz>,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    let midnight = |date: NaiveDate| {
+        let midnight = date.and_time(NaiveTime::MIN);
+        timezone
+            // a time zone skipping midnight for daylight saving time starts the day an hour later
+            .and_then(|tz| tz.from_local_datetime(&midnight).earliest())
+            .map_or(midnight.and_utc(), |start| start.with_timezone(&Utc))
+    };
+    (midnight(date), midnight(date + Duration::days(1)))
+}
+
+pub(crate) fn format_header<Z: TimeZone>(
+    duration: DurationType,
+    location_name: &str,
+    now: DateTime<Z>,
+) -> String
+where
+    Z::Offset: fmt::Display,
+{
+    let period = match duration {
+        DurationType::Now => format!("at {}", now.format("%H:%M")),
+        DurationType::NextHour => String::from("in the next hour"),
+        DurationType::Today => format!("on {}", now.format("%A, %d %B")),
+        DurationType::Tomorrow => format!("on {}", (now + Duration::days(1)).format("%A, %d %B")),
+        DurationType::Week => String::from("this week"),
+        DurationType::Weekend => String::from("this weekend"),
+        DurationType::Morning => String::from("this morning"),
+        DurationType::Afternoon => String::from("this afternoon"),
+        DurationType::Evening => String::from("this evening"),
+        DurationType::Night => String::from("tonight"),
+        DurationType::Custom { start, end } if start == end => {
+            format!("on {}", start.format("%A, %d %B"))
+        }
+        DurationType::Custom { start, end } => format!(
+            "from {} to {}",
+            start.format("%A, %d %B"),
+            end.format("%A, %d %B")
+        ),
+        DurationType::HoursAhead(1) => String::from("over the next hour"),
+        DurationType::HoursAhead(hours) => format!("over the next {} hours", hours),
+    };
+    format!("Weather for {} {}\n", location_name, period)
+}
+
+/// Header of the terminal views; with `--skip-hours` it also tells the actual current time,
+/// since the forecast starts later
+fn view_header(duration: DurationType, location_name: &str, options: &DisplayOptions) -> String {
+    let header = format_header(duration, location_name, display_time(options.now(), options));
+    match options.skip_hours {
+        0 => header,
+        hours => format!(
+            "{} (now {}, skipping {} hours)\n",
+            header.trim_end(),
+            display_time(Utc::now(), options).format("%H:%M"),
+            hours
+        ),
+    }
+}
+
+/// How long ago something happened, e.g. `5 minutes ago`
+pub fn format_age(age: Duration) -> String {
+    match age.num_seconds() {
+        seconds if seconds < 60 => format!("{} seconds ago", seconds),
+        _ => format!("{} minutes ago", age.num_minutes()),
+    }
+}
+
+/// Start and end of the day shown by `duration`, for the single-day views
+fn day_bounds(
+    duration: DurationType,
+    now: DateTime<Utc>,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let midnight = now.date_naive().and_hms_opt(0, 0, 0)?.and_utc();
+    let start = match duration {
+        DurationType::Today => midnight,
+        DurationType::Tomorrow => midnight + Duration::days(1),
+        _ => return None,
+    };
+    Some((start, start + Duration::days(1)))
+}
+
+/// Start and end of the days shown by `duration`, for the views spanning several days
+fn multi_day_bounds(
+    duration: DurationType,
+    now: DateTime<Utc>,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let midnight = |date: NaiveDate| date.and_time(NaiveTime::MIN).and_utc();
+    match duration {
+        DurationType::Week => {
+            let today = midnight(now.date_naive());
+            Some((today, today + Duration::days(7)))
+        }
+        DurationType::Weekend => {
+            let saturday = midnight(weekend_start(now.date_naive()));
+            Some((saturday, saturday + Duration::days(2)))
+        }
+        DurationType::Custom { start, end } => {
+            Some((midnight(start), midnight(end) + Duration::days(1)))
+        }
+        DurationType::HoursAhead(hours) if hours > 24 => {
+            Some((now - Duration::hours(1), now + Duration::hours(hours as i64)))
+        }
+        _ => None,
+    }
+}
+
+/// Start and end of the part of the day shown by `duration`, for the time-slice views
+pub(crate) fn time_slice_bounds(
+    duration: DurationType,
+    now: DateTime<Utc>,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let (from, to) = match duration {
+        DurationType::Morning => (6, 12),
+        DurationType::Afternoon => (12, 18),
+        DurationType::Evening => (18, 22),
+        DurationType::Night => (22, 30),
+        _ => return None,
+    };
+    let midnight = now.date_naive().and_time(NaiveTime::MIN).and_utc();
+    Some((midnight + Duration::hours(from), midnight + Duration::hours(to)))
+}
+
+/// Saturday of the weekend shown by `DurationType::Weekend`
+pub(crate) fn weekend_start(today: NaiveDate) -> NaiveDate {
+    match today.weekday() {
+        Weekday::Sun => today - Duration::days(1),
+        weekday => today + Duration::days(5 - weekday.num_days_from_monday() as i64),
+    }
+}
+
+/// 16-point compass name of a wind direction in degrees
+fn compass_direction(degrees: f64) -> &'static str {
+    COMPASS_POINTS[compass_sector(degrees)]
+}
+
+/// Points of the 16-point compass, clockwise from north
+const COMPASS_POINTS: [&str; 16] = [
+    "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW", "NW",
+    "NNW",
+];
+
+/// Arithmetic mean of `values`, `None` when there are none
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Index in `COMPASS_POINTS` of a direction in degrees
+fn compass_sector(degrees: f64) -> usize {
+    (degrees.rem_euclid(360.0) / 22.5).round() as usize % COMPASS_POINTS.len()
+}
+
+/// Time of `timeseries` in the `--timezone`
+pub(crate) fn entry_time(
+    timeseries: &Timeseries,
+    options: &DisplayOptions,
+) -> DateTime<FixedOffset> {
+    match options.timezone {
+        Some(tz) => timeseries.local_time(&tz).fixed_offset(),
+        None => timeseries.time.fixed_offset(),
+    }
+}
+
+/// Format of the time column of tables, with the weekday when `duration` spans several days
+pub(crate) fn entry_time_format(duration: DurationType) -> &'static str {
+    match duration {
+        DurationType::Week | DurationType::Weekend | DurationType::Custom { .. } => "%a %H:%M",
+        DurationType::HoursAhead(hours) if hours > 24 => "%a %H:%M",
+        _ => "%H:%M",
+    }
+}
+
+fn format_time_prefix(duration: DurationType, time: DateTime<FixedOffset>) -> String {
+    match duration {
+        DurationType::Now => String::new(),
+        DurationType::Week | DurationType::Custom { .. } => {
+            format!("{}: ", time.format("%A %H:%M"))
+        }
+        DurationType::HoursAhead(hours) if hours > 24 => format!("{}: ", time.format("%A %H:%M")),
+        // weekend entries are listed under a heading for their day
+        _ => format!("{}: ", time.format("%H:%M")),
+    }
+}
+
+fn format_weather_description(description: &str) -> String {
+    match WeatherCondition::try_from(SymbolCode(description)) {
+        Ok(condition) => condition.to_string(),
+        Err(_) => description.to_string(), // Default to the original description if not found
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use chrono::DurationRound;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    /// Time `hour` hours after midnight UTC of the first day of the test forecasts
+    pub(crate) fn at(hour: i64) -> DateTime<Utc> {
+        "2026-10-16T00:00:00Z".parse::<DateTime<Utc>>().unwrap() + Duration::hours(hour)
+    }
+
+    /// Forecast with an entry per `(hour, data)`, `data` being the `data` object of met.no
+    pub(crate) fn forecast(entries: Vec<(i64, Value)>) -> WeatherData {
+        forecast_from(at(0), entries)
+    }
+
+    /// Forecast updated at `start` with an entry `hour` hours after it per `(hour, data)`
+    pub(crate) fn forecast_from(start: DateTime<Utc>, entries: Vec<(i64, Value)>) -> WeatherData {
+        let timeseries: Vec<Value> = entries
+            .into_iter()
+            .map(|(hour, data)| json!({ "time": start + Duration::hours(hour), "data": data }))
+            .collect();
+        serde_json::from_value(json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [10.75, 59.91, 0] },
+            "properties": {
+                "meta": { "updated_at": start, "units": {} },
+                "timeseries": timeseries,
+            },
+        }))
+        .unwrap()
+    }
+
+    /// Start of the current hour, for the views that start at the current time
+    pub(crate) fn current_hour() -> DateTime<Utc> {
+        Utc::now().duration_trunc(Duration::hours(1)).unwrap()
+    }
+
+    /// Midnight UTC starting the current day, for the views of whole days
+    pub(crate) fn current_day() -> DateTime<Utc> {
+        Utc::now().duration_trunc(Duration::days(1)).unwrap()
+    }
+
+    /// Forecast of the current day, hourly and `hour` degrees at each hour
+    pub(crate) fn todays_forecast() -> WeatherData {
+        forecast_from(
+            current_day(),
+            (0..24).map(|hour| (hour, hourly("cloudy", hour as f64))).collect(),
+        )
+    }
+
+    /// Entry data of the hourly part of the forecast
+    pub(crate) fn hourly(symbol_code: &str, air_temperature: f64) -> Value {
+        json!({
+            "instant": { "details": { "air_temperature": air_temperature } },
+            "next_1_hours": { "summary": { "symbol_code": symbol_code } },
+        })
+    }
+
+    /// Entry data of a six-hour period, with its precipitation
+    pub(crate) fn six_hourly(symbol_code: &str, precipitation: f64, air_temperature: f64) -> Value {
+        json!({
+            "instant": { "details": { "air_temperature": air_temperature } },
+            "next_6_hours": {
+                "summary": { "symbol_code": symbol_code },
+                "details": { "precipitation_amount": precipitation },
+            },
+        })
+    }
+
+    /// Entry data with the instant values only, as at the end of the forecast
+    pub(crate) fn instant_only(air_temperature: f64) -> Value {
+        json!({ "instant": { "details": { "air_temperature": air_temperature } } })
+    }
+
+    /// Hourly forecast starting at midnight, one entry per symbol code
+    fn hourly_forecast(symbol_codes: &[&str]) -> WeatherData {
+        forecast(
+            (0..)
+                .zip(symbol_codes)
+                .map(|(hour, code)| (hour, hourly(code, 10.0)))
+                .collect(),
+        )
+    }
+
+    /// Symbol codes of the met.no API, as spelled in its documentation
+    const SYMBOL_CODES: &[&str] = &[
+        "clearsky_day", "fair_day", "partlycloudy_day", "cloudy", "rainshowers_day",
+        "rainshowersandthunder_day", "sleetshowers_day", "snowshowers_day", "rain", "heavyrain",
+        "heavyrainandthunder", "sleet", "snow", "snowandthunder", "fog",
+        "sleetshowersandthunder_day", "snowshowersandthunder_day", "rainandthunder",
+        "sleetandthunder", "lightrainshowersandthunder_day", "heavyrainshowersandthunder_day",
+        "lightssleetshowersandthunder_day", "heavysleetshowersandthunder_day",
+        "lightssnowshowersandthunder_day", "heavysnowshowersandthunder_day", "lightrainandthunder",
+        "lightsleetandthunder", "heavysleetandthunder", "lightsnowandthunder",
+        "heavysnowandthunder", "lightrainshowers_day", "heavyrainshowers_day",
+        "lightsleetshowers_day", "heavysleetshowers_day", "lightsnowshowers_day",
+        "heavysnowshowers_day", "lightrain", "lightsleet", "heavysleet", "lightsnow", "heavysnow",
+        "clearsky_night", "fair_night", "partlycloudy_night", "rainshowers_night",
+        "rainshowersandthunder_night", "sleetshowers_night", "snowshowers_night",
+        "sleetshowersandthunder_night", "snowshowersandthunder_night",
+        "lightrainshowersandthunder_night", "heavyrainshowersandthunder_night",
+        "lightssleetshowersandthunder_night", "heavysleetshowersandthunder_night",
+        "lightssnowshowersandthunder_night", "heavysnowshowersandthunder_night",
+        "lightrainshowers_night", "heavyrainshowers_night", "lightsleetshowers_night",
+        "heavysleetshowers_night", "lightsnowshowers_night", "heavysnowshowers_night",
+        "clearsky_polartwilight", "fair_polartwilight", "partlycloudy_polartwilight",
+        "rainshowers_polartwilight", "rainshowersandthunder_polartwilight",
+        "sleetshowers_polartwilight", "snowshowers_polartwilight",
+        "sleetshowersandthunder_polartwilight", "snowshowersandthunder_polartwilight",
+        "lightrainshowersandthunder_polartwilight", "heavyrainshowersandthunder_polartwilight",
+        "lightssleetshowersandthunder_polartwilight", "heavysleetshowersandthunder_polartwilight",
+        "lightssnowshowersandthunder_polartwilight", "heavysnowshowersandthunder_polartwilight",
+        "lightrainshowers_polartwilight", "heavyrainshowers_polartwilight",
+        "lightsleetshowers_polartwilight", "heavysleetshowers_polartwilight",
+        "lightsnowshowers_polartwilight", "heavysnowshowers_polartwilight",
+    ];
+
+    #[test]
+    fn weather_descriptions_have_only_emoji_before_the_text() {
+        for code in SYMBOL_CODES {
+            let description = format_weather_description(code);
+            assert_ne!(description, *code, "no description for {}", code);
+
+            let (emoji, _) = description.split_once(' ').unwrap();
+            assert!(
+                !emoji.chars().any(|c| c.is_ascii_alphanumeric()),
+                "stray characters in the emoji of {}: {}",
+                code,
+                description
+            );
+        }
+    }
+
+    #[test]
+    fn precipitation_type_is_the_kind_in_the_condition() {
+        for (code, precipitation_type) in [
+            ("sleet", PrecipitationType::Sleet),
+            ("heavysleetshowers_day", PrecipitationType::Sleet),
+            ("sleetshowersandthunder_night", PrecipitationType::Sleet),
+            ("snowshowersandthunder_day", PrecipitationType::Snow),
+            ("rainshowersandthunder_day", PrecipitationType::Rain),
+            ("lightrain", PrecipitationType::Rain),
+            ("fog", PrecipitationType::None),
+            ("cloudy", PrecipitationType::None),
+            ("partlycloudy_night", PrecipitationType::None),
+        ] {
+            assert_eq!(
+                SymbolCode(code).precipitation_type(),
+                precipitation_type,
+                "{}",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn severity_ranks_clear_to_thunder() {
+        let severity = |code| SymbolCode(code).severity();
+        assert_eq!(severity("clearsky_day"), 0);
+        assert_eq!(severity("fair_night"), 0);
+        assert_eq!(severity("cloudy"), 1);
+        assert_eq!(severity("fog"), 1);
+        assert_eq!(severity("lightsnow"), 1);
+        assert_eq!(severity("rainshowers_day"), 2);
+        assert!(severity("heavyrain") >= 2);
+        assert_eq!(severity("sleetandthunder"), 3);
+        assert_eq!(severity("lightrainshowersandthunder_night"), 3);
+    }
+
+    #[test]
+    fn clear_weather_window_is_none_without_clear_hours() {
+        assert_eq!(forecast(Vec::new()).get_clear_weather_window(at(0), at(24)), None);
+
+        let weather_data = hourly_forecast(&["cloudy", "rain", "fog"]);
+        assert_eq!(weather_data.get_clear_weather_window(at(0), at(24)), None);
+    }
+
+    #[test]
+    fn clear_weather_window_is_the_longest_run() {
+        let weather_data = hourly_forecast(&[
+            "clearsky_day", "fair_day", "cloudy", "fair_day", "clearsky_day", "fair_night", "rain",
+        ]);
+        assert_eq!(weather_data.get_clear_weather_window(at(0), at(24)), Some((at(3), at(6))));
+    }
+
+    #[test]
+    fn clear_weather_window_prefers_the_earliest_of_equal_runs() {
+        let weather_data =
+            hourly_forecast(&["fair_day", "fair_day", "cloudy", "fair_day", "fair_day"]);
+        assert_eq!(weather_data.get_clear_weather_window(at(0), at(24)), Some((at(0), at(2))));
+    }
+
+    #[test]
+    fn clear_weather_window_is_cut_at_the_bounds() {
+        let weather_data = hourly_forecast(&["fair_day"; 6]);
+        // `from` is inclusive and `to` exclusive, the window ends with the last hour before `to`
+        assert_eq!(weather_data.get_clear_weather_window(at(2), at(4)), Some((at(2), at(4))));
+        assert_eq!(weather_data.get_clear_weather_window(at(4), at(4)), None);
+    }
+
+    #[test]
+    fn clear_weather_window_ends_at_entries_without_an_hourly_summary() {
+        let weather_data = forecast(vec![
+            (0, hourly("clearsky_day", 10.0)),
+            (1, instant_only(10.0)),
+            (2, hourly("clearsky_day", 10.0)),
+            (3, hourly("clearsky_day", 10.0)),
+        ]);
+        assert_eq!(weather_data.get_clear_weather_window(at(0), at(24)), Some((at(2), at(4))));
+    }
+
+    /// Time and temperature of every entry of `weather_data`
+    fn temperatures(weather_data: &WeatherData) -> Vec<(DateTime<Utc>, Option<f64>)> {
+        weather_data
+            .properties
+            .timeseries
+            .iter()
+            .map(|timeseries| (timeseries.time, timeseries.data.instant.details.air_temperature))
+            .collect()
+    }
+
+    #[test]
+    fn merge_appends_entries_after_the_base() {
+        let base = forecast(vec![(0, instant_only(1.0)), (1, instant_only(2.0))]);
+        let update = forecast(vec![(2, instant_only(3.0)), (3, instant_only(4.0))]);
+
+        let merged = WeatherData::merge(base, update);
+        assert_eq!(
+            temperatures(&merged),
+            [(at(0), Some(1.0)), (at(1), Some(2.0)), (at(2), Some(3.0)), (at(3), Some(4.0))]
+        );
+    }
+
+    #[test]
+    fn merge_takes_the_overlap_from_the_newer_forecast() {
+        let base = forecast(vec![
+            (0, instant_only(1.0)),
+            (1, instant_only(1.0)),
+            (2, instant_only(1.0)),
+        ]);
+        let mut update = forecast(vec![(1, instant_only(2.0)), (3, instant_only(2.0))]);
+        update.properties.meta.updated_at = at(1);
+
+        let merged = WeatherData::merge(base, update);
+        assert_eq!(
+            temperatures(&merged),
+            [(at(0), Some(1.0)), (at(1), Some(2.0)), (at(2), Some(1.0)), (at(3), Some(2.0))]
+        );
+        assert_eq!(merged.properties.meta.updated_at, at(1));
+    }
+
+    #[test]
+    fn merge_keeps_the_overlap_of_a_newer_base() {
+        let mut base = forecast(vec![(0, instant_only(1.0)), (1, instant_only(1.0))]);
+        base.properties.meta.updated_at = at(1);
+        let update = forecast(vec![(1, instant_only(2.0)), (2, instant_only(2.0))]);
+
+        let merged = WeatherData::merge(base, update);
+        // entries only in the older forecast are still added
+        assert_eq!(
+            temperatures(&merged),
+            [(at(0), Some(1.0)), (at(1), Some(1.0)), (at(2), Some(2.0))]
+        );
+        assert_eq!(merged.properties.meta.updated_at, at(1));
+    }
+
+    #[test]
+    fn interpolated_temperature_is_linear_between_entries() {
+        let weather_data = forecast(vec![
+            (0, instant_only(10.0)),
+            (1, instant_only(20.0)),
+            (2, json!({ "instant": { "details": { "wind_speed": 3.0 } } })),
+            (3, instant_only(0.0)),
+        ]);
+        let half_past = |hour| at(hour) + Duration::minutes(30);
+
+        assert_eq!(weather_data.interpolate_temperature(half_past(0)), Some(15.0));
+        assert_eq!(weather_data.interpolate_temperature(at(1)), Some(20.0));
+        // entries next to one without a temperature cannot be interpolated between
+        assert_eq!(weather_data.interpolate_temperature(half_past(1)), None);
+        assert_eq!(weather_data.interpolate_temperature(half_past(2)), None);
+        assert_eq!(weather_data.interpolate_temperature(at(-1)), None);
+        assert_eq!(weather_data.interpolate_temperature(half_past(3)), None);
+    }
+
+    #[test]
+    fn week_runs_across_the_end_of_the_month() {
+        let start = "2026-01-28T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let weather_data = forecast_from(
+            start - Duration::days(1),
+            (0..40).map(|block| (6 * block, instant_only(0.0))).collect(),
+        );
+        let week = weather_data.properties.timeseries_for_week(start);
+        let times: Vec<_> = week.iter().map(|timeseries| timeseries.time).collect();
+
+        assert_eq!(times.len(), 7 * 4);
+        assert_eq!(times.first(), Some(&start));
+        assert_eq!(
+            times.last().map(|time| time.to_rfc3339()),
+            Some(String::from("2026-02-03T18:00:00+00:00"))
+        );
+        assert!(times.iter().any(|time| time.month() == 2 && time.day() == 1));
+    }
+
+    #[test]
+    fn days_are_selected_in_the_timezone() {
+        use chrono_tz::Pacific::Auckland;
+
+        let weather_data = forecast((0..48).map(|hour| (hour, hourly("cloudy", 10.0))).collect());
+        let date = at(24).date_naive();
+
+        // 13 hours ahead of UTC in October, so the local day starts at 11:00 UTC the day before
+        let local_day = weather_data
+            .properties
+            .timeseries_for_day(date, Some(Auckland));
+        assert_eq!(local_day.len(), 24);
+        assert_eq!(local_day[0].time, at(11));
+        let utc_day = weather_data.properties.timeseries_for_day(date, None);
+        assert_eq!(utc_day[0].time, at(24));
+
+        let now = Utc::now();
+        let weather_data = forecast_from(
+            current_day() - Duration::days(1),
+            (0..96).map(|hour| (hour, hourly("cloudy", 10.0))).collect(),
+        );
+        let today: Vec<_> = weather_data
+            .lazy_timeseries(DurationType::Today, now, Some(Auckland))
+            .map(|timeseries| timeseries.local_time(&Auckland).date_naive())
+            .collect();
+        // 23 or 25 hours on the days daylight saving time starts or ends
+        assert!((23..=25).contains(&today.len()), "{} hours", today.len());
+        let local_today = now.with_timezone(&Auckland).date_naive();
+        assert!(today.iter().all(|date| *date == local_today));
+    }
+
+    #[test]
+    fn timeseries_at_is_the_nearest_entry() {
+        assert!(forecast(Vec::new()).get_timeseries_at(at(0)).is_none());
+
+        let weather_data = forecast(vec![
+            (0, instant_only(1.0)),
+            (1, instant_only(2.0)),
+            (6, instant_only(3.0)),
+        ]);
+        let time = |t| weather_data.get_timeseries_at(t).map(|timeseries| timeseries.time);
+        assert_eq!(time(at(1)), Some(at(1)));
+        assert_eq!(time(at(1) + Duration::minutes(20)), Some(at(1)));
+        assert_eq!(time(at(4)), Some(at(6)));
+        // the earlier of two equally near entries
+        assert_eq!(time(at(0) + Duration::minutes(30)), Some(at(0)));
+        // before the first and after the last entry
+        assert_eq!(time(at(-5)), Some(at(0)));
+        assert_eq!(time(at(48)), Some(at(6)));
+    }
+
+    #[test]
+    fn exact_timeseries_is_only_an_entry_at_that_time() {
+        assert!(forecast(Vec::new()).get_exact_timeseries(at(0)).is_none());
+
+        let weather_data = forecast(vec![(0, instant_only(1.0)), (1, instant_only(2.0))]);
+        let time = |t| weather_data.get_exact_timeseries(t).map(|timeseries| timeseries.time);
+        assert_eq!(time(at(0)), Some(at(0)));
+        assert_eq!(time(at(1)), Some(at(1)));
+        assert_eq!(time(at(1) + Duration::minutes(1)), None);
+        assert_eq!(time(at(2)), None);
+    }
+
+    #[test]
+    fn fog_periods_are_the_foggy_hours_in_range() {
+        assert!(forecast(Vec::new()).get_fog_periods(at(0), at(24)).is_empty());
+
+        let weather_data = forecast(vec![
+            (0, hourly("fog", 5.0)),
+            (1, hourly("cloudy", 5.0)),
+            (2, hourly("fog", 5.0)),
+            (3, instant_only(5.0)),
+            (4, hourly("fog", 5.0)),
+        ]);
+        let times = |from, to| -> Vec<DateTime<Utc>> {
+            weather_data
+                .get_fog_periods(from, to)
+                .iter()
+                .map(|timeseries| timeseries.time)
+                .collect()
+        };
+        assert_eq!(times(at(0), at(24)), [at(0), at(2), at(4)]);
+        // `to` is exclusive
+        assert_eq!(times(at(1), at(4)), [at(2)]);
+        assert!(times(at(3), at(4)).is_empty());
+    }
+
+    #[test]
+    fn checksum_changes_with_the_forecast() {
+        let weather_data = hourly_forecast(&["fair_day", "cloudy"]);
+        assert_eq!(weather_data.checksum(), hourly_forecast(&["fair_day", "cloudy"]).checksum());
+        assert_ne!(weather_data.checksum(), hourly_forecast(&["fair_day", "rain"]).checksum());
+
+        let mut updated = hourly_forecast(&["fair_day", "cloudy"]);
+        updated.properties.meta.updated_at = at(1);
+        assert_ne!(weather_data.checksum(), updated.checksum());
+    }
+
+    /// Details with an air temperature in °C, relative humidity in % and wind speed in km/h,
+    /// the unit of the wind chill tables
+    fn conditions(temperature: f64, humidity: Option<f64>, wind_speed_kmh: Option<f64>) -> Details {
+        Details {
+            air_pressure_at_sea_level: None,
+            air_temperature: Some(temperature),
+            cloud_area_fraction: None,
+            precipitation_amount: None,
+            relative_humidity: humidity,
+            wind_from_direction: None,
+            wind_speed: wind_speed_kmh.map(|speed| speed / 3.6),
+        }
+    }
+
+    #[test]
+    fn wind_chill_matches_the_environment_canada_table() {
+        // (air temperature °C, wind speed km/h, wind chill of the table)
+        let table = [
+            (10.0, 5.0, 10.0),
+            (0.0, 10.0, -3.0),
+            (-10.0, 20.0, -18.0),
+            (-20.0, 30.0, -33.0),
+            (-30.0, 50.0, -49.0),
+        ];
+        for (temperature, wind_speed, wind_chill) in table {
+            let feels_like = conditions(temperature, None, Some(wind_speed))
+                .feels_like_temperature()
+                .unwrap();
+            assert_eq!(feels_like.round(), wind_chill, "{} °C, {} km/h", temperature, wind_speed);
+        }
+    }
+
+    #[test]
+    fn wind_chill_needs_cold_and_wind() {
+        assert!(conditions(10.5, None, Some(30.0)).feels_like_temperature().is_none());
+        assert!(conditions(-10.0, None, Some(4.7)).feels_like_temperature().is_none());
+        assert!(conditions(-10.0, None, Some(4.8)).feels_like_temperature().is_some());
+        assert!(conditions(-10.0, None, None).feels_like_temperature().is_none());
+    }
+
+    fn celsius(fahrenheit: f64) -> f64 {
+        (fahrenheit - 32.0) * 5.0 / 9.0
+    }
+
+    #[test]
+    fn heat_index_matches_the_nws_table() {
+        // (air temperature °F, relative humidity %, heat index °F of the NWS table)
+        let table = [
+            (90.0, 50.0, 95.0),
+            (90.0, 70.0, 106.0),
+            (96.0, 50.0, 108.0),
+            (100.0, 40.0, 109.0),
+            (84.0, 90.0, 98.0),
+        ];
+        for (temperature, humidity, heat_index) in table {
+            let feels_like = conditions(celsius(temperature), Some(humidity), None)
+                .feels_like_temperature()
+                .unwrap();
+            let fahrenheit = feels_like * 9.0 / 5.0 + 32.0;
+            assert_eq!(fahrenheit.round(), heat_index, "{} °F, {} %", temperature, humidity);
+        }
+    }
+
+    #[test]
+    fn heat_index_needs_heat_and_humidity() {
+        assert!(conditions(26.5, Some(90.0), None).feels_like_temperature().is_none());
+        assert!(conditions(35.0, Some(39.0), None).feels_like_temperature().is_none());
+        assert!(conditions(35.0, None, None).feels_like_temperature().is_none());
+    }
+
+    #[test]
+    fn dew_point_matches_the_reference_table() {
+        // (air temperature °C, relative humidity %, dew point °C)
+        let table = [
+            (0.0, 100.0, 0.0),
+            (10.0, 80.0, 6.7),
+            (20.0, 50.0, 9.3),
+            (25.0, 60.0, 16.7),
+            (30.0, 70.0, 23.9),
+        ];
+        for (temperature, humidity, dew_point) in table {
+            let computed = conditions(temperature, Some(humidity), None)
+                .dew_point_temperature()
+                .unwrap();
+            assert!(
+                (computed - dew_point).abs() < 0.1,
+                "{} °C, {} %: {}",
+                temperature,
+                humidity,
+                computed
+            );
+        }
+        assert!(conditions(20.0, Some(0.0), None).dew_point_temperature().is_none());
+    }
+
+    #[test]
+    fn absolute_humidity_matches_reference_values() {
+        let humidity = |temperature, relative_humidity| {
+            conditions(temperature, Some(relative_humidity), None)
+                .absolute_humidity_g_per_m3()
+                .unwrap()
+        };
+        assert!((humidity(20.0, 50.0) - 8.6).abs() < 0.05);
+        assert!((humidity(30.0, 100.0) - 30.4).abs() < 0.1);
+        assert!((humidity(0.0, 100.0) - 4.8).abs() < 0.05);
+        assert_eq!(humidity(20.0, 0.0), 0.0);
+        assert!(conditions(20.0, None, None).absolute_humidity_g_per_m3().is_none());
     }
 }
@@ -0,0 +1,250 @@
+//! `weather-cli serve --websocket`: forecasts pushed to WebSocket clients
+//!
+//! A client sends `{"subscribe": "<location>"}` or `{"unsubscribe": "<location>"}` and gets a
+//! `weather_update` message with the forecast of the location right away, then again every time
+//! a refresh of the cache finds that met.no has updated it.
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_tungstenite::tungstenite::error::ProtocolError;
+use tokio_tungstenite::tungstenite::{self, Message};
+
+use crate::error::AppError;
+use crate::serve::Server;
+use crate::weather::WeatherData;
+
+/// Updates kept for clients that are slow to read, before they miss some
+const UPDATE_CAPACITY: usize = 64;
+
+impl WeatherData {
+    /// The forecast of `location` in a `weather_update` envelope
+    pub fn to_websocket_message(&self, location: &str) -> String {
+        json!({ "type": "weather_update", "location": location, "data": self }).to_string()
+    }
+}
+
+/// Message telling the client that something about `location`, if any, failed
+fn error_message(location: Option<&str>, error: &str) -> String {
+    json!({ "type": "error", "location": location, "error": error }).to_string()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ClientMessage {
+    Subscribe(String),
+    Unsubscribe(String),
+}
+
+#[derive(Default)]
+struct CachedForecast {
+    subscribers: usize,
+    updated_at: Option<DateTime<Utc>>,
+    message: Option<Arc<str>>,
+}
+
+#[derive(Clone)]
+struct Update {
+    location: String,
+    message: Arc<str>,
+}
+
+/// Last forecast of every location some client is subscribed to
+struct ForecastCache {
+    server: Arc<Server>,
+    forecasts: Mutex<HashMap<String, CachedForecast>>,
+    updates: broadcast::Sender<Update>,
+}
+
+impl ForecastCache {
+    fn new(server: Arc<Server>) -> ForecastCache {
+        ForecastCache {
+            server,
+            forecasts: Mutex::new(HashMap::new()),
+            updates: broadcast::channel(UPDATE_CAPACITY).0,
+        }
+    }
+
+    /// Message with the forecast of `location`, fetched unless it is cached already
+    async fn subscribe(&self, location: &str) -> Result<Arc<str>, AppError> {
+        let cached = {
+            let mut forecasts = self.forecasts.lock().unwrap();
+            let forecast = forecasts.entry(location.to_string()).or_default();
+            forecast.subscribers += 1;
+            forecast.message.clone()
+        };
+        if let Some(message) = cached {
+            return Ok(message);
+        }
+
+        match self.server.forecast(location).await {
+            Ok(weather_data) => Ok(self.store(location, &weather_data)),
+            Err(e) => {
+                self.unsubscribe(location);
+                Err(e)
+            }
+        }
+    }
+
+    fn unsubscribe(&self, location: &str) {
+        let mut forecasts = self.forecasts.lock().unwrap();
+        if let Some(forecast) = forecasts.get_mut(location) {
+            forecast.subscribers -= 1;
+            if forecast.subscribers == 0 {
+                forecasts.remove(location);
+            }
+        }
+    }
+
+    /// Caches `weather_data` as the forecast of `location`, unless nobody is subscribed to it
+    /// anymore, and returns its message
+    fn store(&self, location: &str, weather_data: &WeatherData) -> Arc<str> {
+        let message: Arc<str> = weather_data.to_websocket_message(location).into();
+        if let Some(forecast) = self.forecasts.lock().unwrap().get_mut(location) {
+            forecast.updated_at = Some(weather_data.properties.meta.updated_at);
+            forecast.message = Some(Arc::clone(&message));
+        }
+        message
+    }
+
+    /// Fetches every subscribed location again and sends the forecasts met.no has updated
+    async fn refresh(&self) {
+        let locations: Vec<String> = self.forecasts.lock().unwrap().keys().cloned().collect();
+        for location in locations {
+            let weather_data = match self.server.forecast(&location).await {
+                Ok(weather_data) => weather_data,
+                Err(e) => {
+                    eprintln!("warning: could not refresh {}: {}", location, e);
+                    continue;
+                }
+            };
+            let updated_at = self
+                .forecasts
+                .lock()
+                .unwrap()
+                .get(&location)
+                .and_then(|forecast| forecast.updated_at);
+            if updated_at == Some(weather_data.properties.meta.updated_at) {
+                continue;
+            }
+
+            let message = self.store(&location, &weather_data);
+            // an error only means that no client is connected
+            let _ = self.updates.send(Update { location, message });
+        }
+    }
+}
+
+/// Accepts WebSocket clients on `address` and refreshes their forecasts every `refresh`, until
+/// the process is stopped
+pub(crate) async fn serve_websocket(
+    server: Arc<Server>,
+    address: SocketAddr,
+    refresh: Duration,
+) -> Result<(), AppError> {
+    let listener = TcpListener::bind(address).await?;
+    eprintln!("Streaming updates on ws://{}", address);
+
+    let cache = Arc::new(ForecastCache::new(server));
+    let refreshed = Arc::clone(&cache);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(refresh);
+        // the first tick is immediate, when nobody can be subscribed yet
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            refreshed.refresh().await;
+        }
+    });
+
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                eprintln!("warning: could not accept a WebSocket client: {}", e);
+                continue;
+            }
+        };
+        let cache = Arc::clone(&cache);
+        tokio::spawn(async move {
+            if let Err(e) = handle_client(&cache, stream).await {
+                eprintln!("warning: WebSocket client failed: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_client(cache: &ForecastCache, stream: TcpStream) -> Result<(), tungstenite::Error> {
+    let (mut sink, mut stream) = tokio_tungstenite::accept_async(stream).await?.split();
+    let mut updates = cache.updates.subscribe();
+    let mut subscriptions = HashSet::new();
+
+    let result = loop {
+        let reply = tokio::select! {
+            message = stream.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    handle_message(cache, &mut subscriptions, &text).await
+                }
+                Some(Ok(Message::Close(_))) | None => break Ok(()),
+                // a client that just goes away, e.g. a closed browser tab
+                Some(Err(tungstenite::Error::Protocol(
+                    ProtocolError::ResetWithoutClosingHandshake,
+                ))) => break Ok(()),
+                Some(Ok(_)) => None,
+                Some(Err(e)) => break Err(e),
+            },
+            update = updates.recv() => match update {
+                Ok(update) if subscriptions.contains(&update.location) => Some(update.message),
+                Ok(_) | Err(RecvError::Lagged(_)) => None,
+                Err(RecvError::Closed) => break Ok(()),
+            },
+        };
+        if let Some(reply) = reply {
+            if let Err(e) = sink.send(Message::text(reply.as_ref())).await {
+                break Err(e);
+            }
+        }
+    };
+
+    for location in subscriptions {
+        cache.unsubscribe(&location);
+    }
+    result
+}
+
+/// Reply to the client message `text`, if it needs one
+async fn handle_message(
+    cache: &ForecastCache,
+    subscriptions: &mut HashSet<String>,
+    text: &str,
+) -> Option<Arc<str>> {
+    match serde_json::from_str(text) {
+        Ok(ClientMessage::Subscribe(location)) => {
+            if !subscriptions.insert(location.clone()) {
+                return None;
+            }
+            match cache.subscribe(&location).await {
+                Ok(message) => Some(message),
+                Err(e) => {
+                    subscriptions.remove(&location);
+                    Some(error_message(Some(&location), &e.to_string()).into())
+                }
+            }
+        }
+        Ok(ClientMessage::Unsubscribe(location)) => {
+            if subscriptions.remove(&location) {
+                cache.unsubscribe(&location);
+            }
+            None
+        }
+        Err(e) => Some(error_message(None, &e.to_string()).into()),
+    }
+}